@@ -0,0 +1,48 @@
+//! Compares `Ciphers::Aes256Gcm.aead_cipher()` against the precomputed `Aes256GcmState`
+//!
+//! Run with `cargo bench --bench aes256gcm`
+
+use std::time::{ Duration, Instant };
+use crypto_api_libsodium::{ Ciphers, cipher::Aes256GcmState };
+
+
+/// The amount of messages to seal per run
+const ITERATIONS: usize = 200_000;
+/// The message length
+const MESSAGE_LEN: usize = 64;
+
+
+/// Runs `f` `ITERATIONS` times and returns the elapsed time
+fn measure(mut f: impl FnMut()) -> Duration {
+	let start = Instant::now();
+	(0..ITERATIONS).for_each(|_| f());
+	start.elapsed()
+}
+
+
+fn main() {
+	if !Ciphers::Aes256Gcm.is_available() {
+		println!("AES-256-GCM is not available on this hardware");
+		return;
+	}
+	
+	let (key, nonce, mut buf) = ([7u8; 32], [3u8; 12], [0u8; MESSAGE_LEN + 16]);
+	
+	// Benchmark the stateless implementation
+	let cipher = Ciphers::Aes256Gcm.aead_cipher().unwrap();
+	let stateless = measure(|| {
+		cipher.seal(&mut buf, MESSAGE_LEN, &[], &key, &nonce).unwrap();
+	});
+	
+	// Benchmark the precomputed implementation
+	let state = Aes256GcmState::new(&key).unwrap();
+	let precomputed = measure(|| {
+		state.seal(&mut buf, MESSAGE_LEN, &[], &nonce).unwrap();
+	});
+	
+	// Print the results
+	let per_msg = |d: Duration| d.as_nanos() / ITERATIONS as u128;
+	println!("aead_cipher().seal:    {:>6} ns/message", per_msg(stateless));
+	println!("Aes256GcmState::seal:  {:>6} ns/message", per_msg(precomputed));
+	println!("speedup:               {:>6.2}x", stateless.as_secs_f64() / precomputed.as_secs_f64());
+}
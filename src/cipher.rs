@@ -1,13 +1,35 @@
 use crate::{
 	LibsodiumError, SystemRng,
 	sodium_bindings::{
-		sodium_init, crypto_stream_chacha20_ietf_xor,
+		sodium_init, crypto_stream_chacha20_ietf_xor, crypto_stream_xchacha20_xor,
+		crypto_stream_xsalsa20_xor, crypto_stream_salsa20_xor, crypto_stream_salsa2012_xor,
+		crypto_stream_salsa208_xor, crypto_stream_chacha20_ietf_xor_ic, crypto_stream_xchacha20_xor_ic,
+		crypto_stream_xsalsa20_xor_ic, crypto_stream_salsa20_xor_ic, sodium_memzero,
 		crypto_aead_aes256gcm_encrypt, crypto_aead_aes256gcm_decrypt,
-		crypto_aead_chacha20poly1305_ietf_encrypt, crypto_aead_chacha20poly1305_ietf_decrypt
+		crypto_aead_chacha20poly1305_ietf_encrypt, crypto_aead_chacha20poly1305_ietf_decrypt,
+		crypto_aead_chacha20poly1305_encrypt, crypto_aead_chacha20poly1305_decrypt,
+		crypto_aead_xchacha20poly1305_ietf_encrypt, crypto_aead_xchacha20poly1305_ietf_decrypt,
+		crypto_aead_aes256gcm_encrypt_detached, crypto_aead_aes256gcm_decrypt_detached,
+		crypto_aead_chacha20poly1305_ietf_encrypt_detached,
+		crypto_aead_chacha20poly1305_ietf_decrypt_detached,
+		crypto_aead_chacha20poly1305_encrypt_detached, crypto_aead_chacha20poly1305_decrypt_detached,
+		crypto_aead_xchacha20poly1305_ietf_encrypt_detached,
+		crypto_aead_xchacha20poly1305_ietf_decrypt_detached,
+		crypto_aead_aes256gcm_beforenm, crypto_aead_aes256gcm_state,
+		crypto_aead_aes256gcm_encrypt_afternm, crypto_aead_aes256gcm_decrypt_afternm,
+		crypto_aead_aes256gcm_encrypt_detached_afternm,
+		crypto_aead_aes256gcm_decrypt_detached_afternm,
+		sodium_malloc, sodium_free, sodium_mprotect_readonly,
+		crypto_secretbox_easy, crypto_secretbox_open_easy,
+		crypto_secretbox_xchacha20poly1305_easy, crypto_secretbox_xchacha20poly1305_open_easy,
+		crypto_aead_aes256gcm_is_available,
+		crypto_generichash_blake2b_salt_personal, sodium_memcmp, crypto_kdf_derive_from_key,
+		crypto_generichash_blake2b_state, crypto_generichash_blake2b_init,
+		crypto_generichash_blake2b_update, crypto_generichash_blake2b_final
 	}
 };
 use std::{
-	ptr, error::Error,
+	ptr, mem, cmp, error::Error,
 	os::raw::{ c_uchar, c_int, c_ulonglong }
 };
 use crypto_api::{
@@ -31,6 +53,18 @@ struct Aead {
 		c: *const c_uchar, clen: c_ulonglong,
 		ad: *const c_uchar, adlen: c_ulonglong,
 		npub: *const c_uchar, k: *const c_uchar,
+	) -> c_int,
+	pub encrypt_detached: unsafe extern "C" fn(
+		c: *mut c_uchar, mac: *mut c_uchar, maclen_p: *mut c_ulonglong,
+		m: *const c_uchar, mlen: c_ulonglong,
+		ad: *const c_uchar, adlen: c_ulonglong,
+		nsec: *const c_uchar, npub: *const c_uchar, k: *const c_uchar
+	) -> c_int,
+	pub decrypt_detached: unsafe extern "C" fn(
+		m: *mut c_uchar, nsec: *mut c_uchar,
+		c: *const c_uchar, clen: c_ulonglong, mac: *const c_uchar,
+		ad: *const c_uchar, adlen: c_ulonglong,
+		npub: *const c_uchar, k: *const c_uchar
 	) -> c_int
 }
 impl Cipher for Aead {
@@ -44,6 +78,14 @@ impl Cipher for Aead {
 				name: "ChaCha20Poly1305Ietf", key_len: 32, nonce_len: 12,
 				aead_tag_len: Some(16)
 			},
+			Ciphers::XChaCha20Poly1305Ietf => CipherInfo {
+				name: "XChaCha20Poly1305Ietf", key_len: 32, nonce_len: 24,
+				aead_tag_len: Some(16)
+			},
+			Ciphers::ChaCha20Poly1305 => CipherInfo {
+				name: "ChaCha20Poly1305", key_len: 32, nonce_len: 8,
+				aead_tag_len: Some(16)
+			},
 			_ => unreachable!()
 		}
 	}
@@ -72,6 +114,7 @@ impl AeadCipher for Aead {
 	{
 		// Check parameters
 		let info = self.info();
+		check!(self.cipher.is_available(), LibsodiumError::Unsupported);
 		check!(
 			buf.len() >= self.encrypted_len_max(plaintext_len),
 			LibsodiumError::ApiMisuse("Buffer is too small")
@@ -93,6 +136,7 @@ impl AeadCipher for Aead {
 	{
 		// Check parameters
 		let info = self.info();
+		check!(self.cipher.is_available(), LibsodiumError::Unsupported);
 		check!(
 			buf.len() >= ciphertext_len,
 			LibsodiumError::ApiMisuse("Buffer is too small")
@@ -110,6 +154,54 @@ impl AeadCipher for Aead {
 		Ok(ciphertext_len - info.aead_tag_len.unwrap())
 	}
 }
+impl DetachedAeadCipher for Aead {
+	fn seal_detached(&self, buf: &mut[u8], plaintext_len: usize, tag: &mut[u8], ad: &[u8],
+		key: &[u8], nonce: &[u8]) -> Result<usize, Box<dyn Error>>
+	{
+		// Check parameters
+		let info = self.info();
+		check!(self.cipher.is_available(), LibsodiumError::Unsupported);
+		check!(buf.len() >= plaintext_len, LibsodiumError::ApiMisuse("Buffer is too small"));
+		check!(
+			tag.len() >= info.aead_tag_len.unwrap(),
+			LibsodiumError::ApiMisuse("Tag buffer is too small")
+		);
+		check!(key.len() == info.key_len, LibsodiumError::ApiMisuse("Invalid key length"));
+		check!(nonce.len() == info.nonce_len, LibsodiumError::ApiMisuse("Invalid nonce length"));
+		
+		// Call libsodium
+		assert_eq!(sodium!(
+			buf.as_mut_ptr(), tag.as_mut_ptr(), ptr::null_mut(), buf.as_ptr(), plaintext_len,
+			ad.as_ptr(), ad.len(), ptr::null(), nonce.as_ptr(), key.as_ptr()
+				=> self.encrypt_detached
+		), 0);
+		Ok(plaintext_len)
+	}
+	
+	fn open_detached(&self, buf: &mut[u8], ciphertext_len: usize, tag: &[u8], ad: &[u8],
+		key: &[u8], nonce: &[u8]) -> Result<usize, Box<dyn Error>>
+	{
+		// Check parameters
+		let info = self.info();
+		check!(self.cipher.is_available(), LibsodiumError::Unsupported);
+		check!(buf.len() >= ciphertext_len, LibsodiumError::ApiMisuse("Buffer is too small"));
+		check!(
+			tag.len() == info.aead_tag_len.unwrap(),
+			LibsodiumError::ApiMisuse("Invalid tag length")
+		);
+		check!(key.len() == info.key_len, LibsodiumError::ApiMisuse("Invalid key length"));
+		check!(nonce.len() == info.nonce_len, LibsodiumError::ApiMisuse("Invalid nonce length"));
+		
+		// Call libsodium
+		let result = sodium!(
+			buf.as_mut_ptr(), ptr::null_mut::<c_uchar>(), buf.as_ptr(), ciphertext_len,
+			tag.as_ptr(), ad.as_ptr(), ad.len(), nonce.as_ptr(), key.as_ptr()
+				=> self.decrypt_detached
+		);
+		check!(result == 0, LibsodiumError::InvalidData);
+		Ok(ciphertext_len)
+	}
+}
 impl SecKeyGen for Aead {
 	fn new_sec_key(&self, buf: &mut[u8]) -> Result<usize, Box<dyn Error + 'static>> {
 		// Check the buffer length
@@ -123,6 +215,385 @@ impl SecKeyGen for Aead {
 }
 
 
+/// A NaCl-compatible secretbox implementation (the layout is `tag || ciphertext`)
+struct Secretbox {
+	pub cipher: Ciphers,
+	pub seal: unsafe extern "C" fn(
+		c: *mut c_uchar,
+		m: *const c_uchar, mlen: c_ulonglong,
+		n: *const c_uchar, k: *const c_uchar
+	) -> c_int,
+	pub open: unsafe extern "C" fn(
+		m: *mut c_uchar,
+		c: *const c_uchar, clen: c_ulonglong,
+		n: *const c_uchar, k: *const c_uchar
+	) -> c_int
+}
+impl Cipher for Secretbox {
+	fn info(&self) -> CipherInfo {
+		match self.cipher {
+			Ciphers::XSalsa20Poly1305 => CipherInfo {
+				name: "XSalsa20Poly1305", key_len: 32, nonce_len: 24,
+				aead_tag_len: Some(16)
+			},
+			Ciphers::XChaCha20Poly1305 => CipherInfo {
+				name: "XChaCha20Poly1305", key_len: 32, nonce_len: 24,
+				aead_tag_len: Some(16)
+			},
+			_ => unreachable!()
+		}
+	}
+	
+	
+	fn encrypted_len_max(&self, plaintext_len: usize) -> usize {
+		plaintext_len + self.info().aead_tag_len.unwrap()
+	}
+	
+	
+	fn encrypt(&self, buf: &mut[u8], plaintext_len: usize, key: &[u8], nonce: &[u8])
+		-> Result<usize, Box<dyn Error>>
+	{
+		// Check parameters
+		let info = self.info();
+		check!(
+			buf.len() >= self.encrypted_len_max(plaintext_len),
+			LibsodiumError::ApiMisuse("Buffer is too small")
+		);
+		check!(key.len() == info.key_len, LibsodiumError::ApiMisuse("Invalid key length"));
+		check!(nonce.len() == info.nonce_len, LibsodiumError::ApiMisuse("Invalid nonce length"));
+		
+		// Call libsodium (which supports overlapping buffers)
+		assert_eq!(sodium!(
+			buf.as_mut_ptr(), buf.as_ptr(), plaintext_len, nonce.as_ptr(), key.as_ptr()
+				=> self.seal
+		), 0);
+		Ok(plaintext_len + info.aead_tag_len.unwrap())
+	}
+	
+	fn decrypt(&self, buf: &mut[u8], ciphertext_len: usize, key: &[u8], nonce: &[u8])
+		-> Result<usize, Box<dyn Error>>
+	{
+		// Check parameters
+		let info = self.info();
+		check!(
+			buf.len() >= ciphertext_len,
+			LibsodiumError::ApiMisuse("Buffer is too small")
+		);
+		check!(key.len() == info.key_len, LibsodiumError::ApiMisuse("Invalid key length"));
+		check!(nonce.len() == info.nonce_len, LibsodiumError::ApiMisuse("Invalid nonce length"));
+		
+		// Call libsodium (which supports overlapping buffers)
+		let result = sodium!(
+			buf.as_mut_ptr(), buf.as_ptr(), ciphertext_len, nonce.as_ptr(), key.as_ptr()
+				=> self.open
+		);
+		check!(result == 0, LibsodiumError::InvalidData);
+		Ok(ciphertext_len - info.aead_tag_len.unwrap())
+	}
+}
+impl SecKeyGen for Secretbox {
+	fn new_sec_key(&self, buf: &mut[u8]) -> Result<usize, Box<dyn Error + 'static>> {
+		// Check the buffer length
+		let key_len = self.info().key_len;
+		check!(buf.len() >= key_len, LibsodiumError::ApiMisuse("Buffer is too small"));
+		
+		// Generate the key
+		SystemRng.random(&mut buf[..key_len])?;
+		Ok(key_len)
+	}
+}
+
+
+/// The length of the key commitment
+const COMMITMENT_LEN: usize = 32;
+/// The BLAKE2b personalization used to derive the encryption key and the commitment
+const COMMITMENT_PERSONAL: &[u8; 16] = b"KeyCommitAead\x00\x00\x00";
+
+
+/// A key-committing wrapper around an AEAD implementation (the layout is
+/// `commitment || ciphertext || tag`)
+///
+/// The encryption key and the commitment are derived as
+/// `encryption key || commitment = BLAKE2b-512(key = key, in = nonce, personal = "KeyCommitAead")`;
+/// the commitment is verified in constant time before any decryption takes place.
+struct Committing {
+	pub cipher: Ciphers,
+	pub aead: Aead
+}
+impl Committing {
+	/// Derives the encryption key and the commitment from `key` and `nonce`
+	fn derive(key: &[u8], nonce: &[u8]) -> [u8; 64] {
+		let mut derived = [0; 64];
+		assert_eq!(sodium!(
+			derived.as_mut_ptr(), derived.len(), nonce.as_ptr(), nonce.len(),
+			key.as_ptr(), key.len(), ptr::null::<c_uchar>(), COMMITMENT_PERSONAL.as_ptr()
+				=> crypto_generichash_blake2b_salt_personal
+		), 0);
+		derived
+	}
+}
+impl Cipher for Committing {
+	fn info(&self) -> CipherInfo {
+		let info = self.aead.info();
+		let name = match self.cipher {
+			Ciphers::Aes256GcmCommitting => "Aes256GcmCommitting",
+			Ciphers::ChaCha20Poly1305IetfCommitting => "ChaCha20Poly1305IetfCommitting",
+			Ciphers::XChaCha20Poly1305IetfCommitting => "XChaCha20Poly1305IetfCommitting",
+			_ => unreachable!()
+		};
+		CipherInfo{ name, aead_tag_len: Some(COMMITMENT_LEN + info.aead_tag_len.unwrap()), ..info }
+	}
+	
+	
+	fn encrypted_len_max(&self, plaintext_len: usize) -> usize {
+		plaintext_len + self.info().aead_tag_len.unwrap()
+	}
+	
+	
+	fn encrypt(&self, buf: &mut[u8], plaintext_len: usize, key: &[u8], nonce: &[u8])
+		-> Result<usize, Box<dyn Error>>
+	{
+		self.seal(buf, plaintext_len, &[], key, nonce)
+	}
+	
+	fn decrypt(&self, buf: &mut[u8], ciphertext_len: usize, key: &[u8], nonce: &[u8])
+		-> Result<usize, Box<dyn Error>>
+	{
+		self.open(buf, ciphertext_len, &[], key, nonce)
+	}
+}
+impl AeadCipher for Committing {
+	fn seal(&self, buf: &mut[u8], plaintext_len: usize, ad: &[u8], key: &[u8], nonce: &[u8])
+		-> Result<usize, Box<dyn Error>>
+	{
+		// Check parameters
+		let info = self.info();
+		check!(self.cipher.is_available(), LibsodiumError::Unsupported);
+		check!(
+			buf.len() >= self.encrypted_len_max(plaintext_len),
+			LibsodiumError::ApiMisuse("Buffer is too small")
+		);
+		check!(key.len() == info.key_len, LibsodiumError::ApiMisuse("Invalid key length"));
+		check!(nonce.len() == info.nonce_len, LibsodiumError::ApiMisuse("Invalid nonce length"));
+		
+		// Derive the key and seal the plaintext behind the commitment
+		let mut derived = Self::derive(key, nonce);
+		let (derived_key, commitment) = derived.split_at(info.key_len);
+		buf.copy_within(..plaintext_len, COMMITMENT_LEN);
+		let result = self.aead.seal(
+			&mut buf[COMMITMENT_LEN..], plaintext_len, ad, derived_key, nonce
+		);
+		buf[..COMMITMENT_LEN].copy_from_slice(commitment);
+		sodium!(derived.as_mut_ptr(), derived.len() => sodium_memzero);
+		
+		Ok(COMMITMENT_LEN + result?)
+	}
+	
+	fn open(&self, buf: &mut[u8], ciphertext_len: usize, ad: &[u8], key: &[u8], nonce: &[u8])
+		-> Result<usize, Box<dyn Error>>
+	{
+		// Check parameters
+		let info = self.info();
+		check!(self.cipher.is_available(), LibsodiumError::Unsupported);
+		check!(
+			buf.len() >= ciphertext_len,
+			LibsodiumError::ApiMisuse("Buffer is too small")
+		);
+		check!(key.len() == info.key_len, LibsodiumError::ApiMisuse("Invalid key length"));
+		check!(nonce.len() == info.nonce_len, LibsodiumError::ApiMisuse("Invalid nonce length"));
+		check!(ciphertext_len >= info.aead_tag_len.unwrap(), LibsodiumError::InvalidData);
+		
+		// Derive the key and verify the commitment
+		let mut derived = Self::derive(key, nonce);
+		let (derived_key, commitment) = derived.split_at(info.key_len);
+		let result = match sodium!(
+			buf.as_ptr(), commitment.as_ptr(), COMMITMENT_LEN => sodium_memcmp
+		) {
+			0 => self.aead.open(
+				&mut buf[COMMITMENT_LEN..], ciphertext_len - COMMITMENT_LEN, ad, derived_key, nonce
+			),
+			_ => Err(LibsodiumError::InvalidData.into())
+		};
+		sodium!(derived.as_mut_ptr(), derived.len() => sodium_memzero);
+		
+		// Move the plaintext to the beginning of the buffer
+		let plaintext_len = result?;
+		buf.copy_within(COMMITMENT_LEN..COMMITMENT_LEN + plaintext_len, 0);
+		Ok(plaintext_len)
+	}
+}
+impl SecKeyGen for Committing {
+	fn new_sec_key(&self, buf: &mut[u8]) -> Result<usize, Box<dyn Error + 'static>> {
+		self.aead.new_sec_key(buf)
+	}
+}
+
+
+/// The length of the synthetic IV (which is also the XChaCha20 nonce length)
+const SIV_LEN: usize = 24;
+/// The KDF context used to derive the MAC and the encryption key
+const SIV_KDF_CONTEXT: &[u8; 8] = b"XChaCSIV";
+
+
+/// A deterministic, nonce-misuse-resistant XChaCha20-SIV implementation (the layout is
+/// `synthetic IV || ciphertext`)
+///
+/// The MAC key and the encryption key are derived from the key with `crypto_kdf` (IDs 1 and 2).
+/// The synthetic IV is a keyed BLAKE2b-192 over
+/// `nonce_len (u64 LE) || nonce || ad_len (u64 LE) || ad || plaintext` and is used as XChaCha20
+/// nonce.
+struct Siv;
+impl Siv {
+	/// Derives the subkey with the ID `id` from `key`
+	fn derive(key: &[u8], id: u64) -> [u8; 32] {
+		let mut subkey = [0; 32];
+		assert_eq!(sodium!(
+			subkey.as_mut_ptr(), subkey.len(), id, SIV_KDF_CONTEXT.as_ptr(), key.as_ptr()
+				=> crypto_kdf_derive_from_key
+		), 0);
+		subkey
+	}
+	/// Computes the synthetic IV over `nonce`, `ad` and `plaintext`
+	fn siv(key: &[u8], nonce: &[u8], ad: &[u8], plaintext: &[u8]) -> [u8; SIV_LEN] {
+		// Initialize the MAC
+		let mut mac_key = Self::derive(key, 1);
+		let mut state: crypto_generichash_blake2b_state = unsafe{ mem::zeroed() };
+		let state_ptr = &mut state as *mut crypto_generichash_blake2b_state;
+		assert_eq!(sodium!(
+			state_ptr, mac_key.as_ptr(), mac_key.len(), SIV_LEN => crypto_generichash_blake2b_init
+		), 0);
+		
+		// Authenticate the length-prefixed nonce and AD and the plaintext
+		let nonce_len = (nonce.len() as u64).to_le_bytes();
+		let ad_len = (ad.len() as u64).to_le_bytes();
+		for data in [&nonce_len, nonce, &ad_len, ad, plaintext].iter() {
+			assert_eq!(sodium!(
+				state_ptr, data.as_ptr(), data.len() => crypto_generichash_blake2b_update
+			), 0);
+		}
+		
+		// Finalize the MAC and erase the key and state
+		let mut siv = [0; SIV_LEN];
+		assert_eq!(sodium!(
+			state_ptr, siv.as_mut_ptr(), SIV_LEN => crypto_generichash_blake2b_final
+		), 0);
+		sodium!(mac_key.as_mut_ptr(), mac_key.len() => sodium_memzero);
+		sodium!(state_ptr, mem::size_of::<crypto_generichash_blake2b_state>() => sodium_memzero);
+		siv
+	}
+	/// Encrypts/decrypts `data` in place with the synthetic IV `siv`
+	fn xor(key: &[u8], siv: &[u8], data: &mut[u8]) {
+		let mut enc_key = Self::derive(key, 2);
+		assert_eq!(sodium!(
+			data.as_mut_ptr(), data.as_ptr(), data.len(), siv.as_ptr(), enc_key.as_ptr()
+				=> crypto_stream_xchacha20_xor
+		), 0);
+		sodium!(enc_key.as_mut_ptr(), enc_key.len() => sodium_memzero);
+	}
+	
+	/// Checks the key and nonce length
+	fn check_key_nonce(&self, key: &[u8], nonce: &[u8]) -> Result<(), LibsodiumError> {
+		let info = self.info();
+		check!(key.len() == info.key_len, LibsodiumError::ApiMisuse("Invalid key length"));
+		check!(
+			nonce.is_empty() || nonce.len() == info.nonce_len,
+			LibsodiumError::ApiMisuse("Invalid nonce length")
+		);
+		Ok(())
+	}
+}
+impl Cipher for Siv {
+	fn info(&self) -> CipherInfo {
+		CipherInfo{ name: "XChaCha20Siv", key_len: 32, nonce_len: 24, aead_tag_len: Some(SIV_LEN) }
+	}
+	
+	
+	fn encrypted_len_max(&self, plaintext_len: usize) -> usize {
+		plaintext_len + SIV_LEN
+	}
+	
+	
+	fn encrypt(&self, buf: &mut[u8], plaintext_len: usize, key: &[u8], nonce: &[u8])
+		-> Result<usize, Box<dyn Error>>
+	{
+		self.seal(buf, plaintext_len, &[], key, nonce)
+	}
+	
+	fn decrypt(&self, buf: &mut[u8], ciphertext_len: usize, key: &[u8], nonce: &[u8])
+		-> Result<usize, Box<dyn Error>>
+	{
+		self.open(buf, ciphertext_len, &[], key, nonce)
+	}
+}
+impl AeadCipher for Siv {
+	fn seal(&self, buf: &mut[u8], plaintext_len: usize, ad: &[u8], key: &[u8], nonce: &[u8])
+		-> Result<usize, Box<dyn Error>>
+	{
+		// Check parameters
+		check!(
+			buf.len() >= self.encrypted_len_max(plaintext_len),
+			LibsodiumError::ApiMisuse("Buffer is too small")
+		);
+		self.check_key_nonce(key, nonce)?;
+		
+		// Compute the synthetic IV, move the plaintext behind it and encrypt the plaintext
+		let siv = Self::siv(key, nonce, ad, &buf[..plaintext_len]);
+		buf.copy_within(..plaintext_len, SIV_LEN);
+		buf[..SIV_LEN].copy_from_slice(&siv);
+		Self::xor(key, &siv, &mut buf[SIV_LEN..SIV_LEN + plaintext_len]);
+		Ok(SIV_LEN + plaintext_len)
+	}
+	
+	fn open(&self, buf: &mut[u8], ciphertext_len: usize, ad: &[u8], key: &[u8], nonce: &[u8])
+		-> Result<usize, Box<dyn Error>>
+	{
+		// Check parameters
+		check!(buf.len() >= ciphertext_len, LibsodiumError::ApiMisuse("Buffer is too small"));
+		self.check_key_nonce(key, nonce)?;
+		check!(ciphertext_len >= SIV_LEN, LibsodiumError::InvalidData);
+		
+		// Decrypt the ciphertext and move the plaintext to the beginning of the buffer
+		let plaintext_len = ciphertext_len - SIV_LEN;
+		let mut siv = [0; SIV_LEN];
+		siv.copy_from_slice(&buf[..SIV_LEN]);
+		Self::xor(key, &siv, &mut buf[SIV_LEN..ciphertext_len]);
+		buf.copy_within(SIV_LEN..ciphertext_len, 0);
+		
+		// Verify the synthetic IV and erase the whole decrypted buffer if it does not match
+		let expected = Self::siv(key, nonce, ad, &buf[..plaintext_len]);
+		if sodium!(siv.as_ptr(), expected.as_ptr(), SIV_LEN => sodium_memcmp) != 0 {
+			sodium!(buf.as_mut_ptr(), ciphertext_len => sodium_memzero);
+			Err(LibsodiumError::InvalidData)?
+		}
+		Ok(plaintext_len)
+	}
+}
+impl SecKeyGen for Siv {
+	fn new_sec_key(&self, buf: &mut[u8]) -> Result<usize, Box<dyn Error + 'static>> {
+		// Check the buffer length
+		let key_len = self.info().key_len;
+		check!(buf.len() >= key_len, LibsodiumError::ApiMisuse("Buffer is too small"));
+		
+		// Generate the key
+		SystemRng.random(&mut buf[..key_len])?;
+		Ok(key_len)
+	}
+}
+
+
+/// The block length of the ChaCha20 and Salsa20 stream ciphers
+const STREAM_BLOCK_LEN: u64 = 64;
+
+
+/// Adapts `crypto_stream_chacha20_ietf_xor_ic` (which uses a 32 bit counter) to the 64 bit counter
+/// signature of the other stream ciphers
+unsafe extern "C" fn crypto_stream_chacha20_ietf_xor_ic64(c: *mut c_uchar, m: *const c_uchar,
+	mlen: c_ulonglong, n: *const c_uchar, ic: u64, k: *const c_uchar) -> c_int
+{
+	crypto_stream_chacha20_ietf_xor_ic(c, m, mlen, n, ic as u32, k)
+}
+
+
 /// A XOR stream cipher implementation
 struct Xor {
 	pub cipher: Ciphers,
@@ -132,7 +603,15 @@ struct Xor {
 		mlen: c_ulonglong,
 		n: *const c_uchar,
 		k: *const c_uchar,
-	) -> c_int
+	) -> c_int,
+	pub xor_ic: Option<unsafe extern "C" fn(
+		c: *mut c_uchar,
+		m: *const c_uchar,
+		mlen: c_ulonglong,
+		n: *const c_uchar,
+		ic: u64,
+		k: *const c_uchar,
+	) -> c_int>
 }
 impl Cipher for Xor {
 	fn info(&self) -> CipherInfo {
@@ -141,6 +620,26 @@ impl Cipher for Xor {
 				name: "ChaCha20Ietf", key_len: 32, nonce_len: 12,
 				aead_tag_len: None
 			},
+			Ciphers::XChaCha20 => CipherInfo {
+				name: "XChaCha20", key_len: 32, nonce_len: 24,
+				aead_tag_len: None
+			},
+			Ciphers::XSalsa20 => CipherInfo {
+				name: "XSalsa20", key_len: 32, nonce_len: 24,
+				aead_tag_len: None
+			},
+			Ciphers::Salsa20 => CipherInfo {
+				name: "Salsa20", key_len: 32, nonce_len: 8,
+				aead_tag_len: None
+			},
+			Ciphers::Salsa2012 => CipherInfo {
+				name: "Salsa2012", key_len: 32, nonce_len: 8,
+				aead_tag_len: None
+			},
+			Ciphers::Salsa208 => CipherInfo {
+				name: "Salsa208", key_len: 32, nonce_len: 8,
+				aead_tag_len: None
+			},
 			_ => unreachable!()
 		}
 	}
@@ -177,6 +676,65 @@ impl Cipher for Xor {
 		self.encrypt(buf, ciphertext_len, key, nonce)
 	}
 }
+impl SeekableCipher for Xor {
+	fn encrypt_at(&self, buf: &mut[u8], plaintext_len: usize, key: &[u8], nonce: &[u8],
+		offset: u64) -> Result<usize, Box<dyn Error>>
+	{
+		// Check parameters
+		let info = self.info();
+		let xor_ic = self.xor_ic.ok_or(LibsodiumError::Unsupported)?;
+		check!(buf.len() >= plaintext_len, LibsodiumError::ApiMisuse("Buffer is too small"));
+		check!(key.len() == info.key_len, LibsodiumError::ApiMisuse("Invalid key length"));
+		check!(nonce.len() == info.nonce_len, LibsodiumError::ApiMisuse("Invalid nonce length"));
+		
+		// Check that the block counter does not overflow
+		let counter_max = match self.cipher {
+			Ciphers::ChaCha20Ietf => u32::MAX as u64,
+			_ => u64::MAX
+		};
+		let end = offset.checked_add(plaintext_len as u64)
+			.ok_or(LibsodiumError::ApiMisuse("Offset is too large"))?;
+		check!(
+			plaintext_len == 0 || (end - 1) / STREAM_BLOCK_LEN <= counter_max,
+			LibsodiumError::ApiMisuse("Offset is too large")
+		);
+		
+		// Process the unaligned head
+		let (mut counter, skip) = (offset / STREAM_BLOCK_LEN, (offset % STREAM_BLOCK_LEN) as usize);
+		let mut pos = 0;
+		if skip != 0 {
+			let mut block = [0u8; STREAM_BLOCK_LEN as usize];
+			let head_len = cmp::min(block.len() - skip, plaintext_len);
+			
+			block[skip..skip + head_len].copy_from_slice(&buf[..head_len]);
+			assert_eq!(sodium!(
+				block.as_mut_ptr(), block.as_ptr(), block.len(), nonce.as_ptr(), counter, key.as_ptr()
+					=> xor_ic
+			), 0);
+			buf[..head_len].copy_from_slice(&block[skip..skip + head_len]);
+			sodium!(block.as_mut_ptr(), block.len() => sodium_memzero);
+			
+			counter += 1;
+			pos = head_len;
+		}
+		
+		// Process the remaining (aligned) data
+		if pos < plaintext_len {
+			let buf = &mut buf[pos..plaintext_len];
+			assert_eq!(sodium!(
+				buf.as_mut_ptr(), buf.as_ptr(), buf.len(), nonce.as_ptr(), counter, key.as_ptr()
+					=> xor_ic
+			), 0);
+		}
+		Ok(plaintext_len)
+	}
+	
+	fn decrypt_at(&self, buf: &mut[u8], ciphertext_len: usize, key: &[u8], nonce: &[u8],
+		offset: u64) -> Result<usize, Box<dyn Error>>
+	{
+		self.encrypt_at(buf, ciphertext_len, key, nonce, offset)
+	}
+}
 impl SecKeyGen for Xor {
 	fn new_sec_key(&self, buf: &mut[u8]) -> Result<usize, Box<dyn Error + 'static>> {
 		// Check the buffer length
@@ -190,6 +748,156 @@ impl SecKeyGen for Xor {
 }
 
 
+/// An AES-256-GCM key with a precomputed key schedule
+///
+/// Use this if you seal/open many messages with the same key. The expanded key is stored in
+/// guarded, read-only memory that is zeroed on drop.
+pub struct Aes256GcmState {
+	state: *mut crypto_aead_aes256gcm_state
+}
+impl Aes256GcmState {
+	/// The key length
+	pub const KEY_LEN: usize = 32;
+	/// The nonce length
+	pub const NONCE_LEN: usize = 12;
+	/// The authentication tag length
+	pub const TAG_LEN: usize = 16;
+	
+	/// Expands `key` into a new state
+	///
+	/// Returns `LibsodiumError::Unsupported` if AES-256-GCM is not available on the current
+	/// hardware
+	pub fn new(key: &[u8]) -> Result<Self, LibsodiumError> {
+		// Check parameters
+		check!(Ciphers::Aes256Gcm.is_available(), LibsodiumError::Unsupported);
+		check!(key.len() == Self::KEY_LEN, LibsodiumError::ApiMisuse("Invalid key length"));
+		
+		// Allocate the guarded memory
+		let state: *mut crypto_aead_aes256gcm_state =
+			sodium!(mem::size_of::<crypto_aead_aes256gcm_state>() => sodium_malloc) as _;
+		check!(!state.is_null(), LibsodiumError::ResourceError);
+		
+		// Expand the key and make the state read-only
+		assert_eq!(sodium!(state, key.as_ptr() => crypto_aead_aes256gcm_beforenm), 0);
+		assert_eq!(sodium!(state => sodium_mprotect_readonly), 0);
+		Ok(Self{ state })
+	}
+	
+	/// Seals the first `plaintext_len` bytes in `buf` in place and appends the authentication tag;
+	/// returns the ciphertext length
+	pub fn seal(&self, buf: &mut[u8], plaintext_len: usize, ad: &[u8], nonce: &[u8])
+		-> Result<usize, Box<dyn Error>>
+	{
+		// Check parameters
+		check!(
+			buf.len() >= plaintext_len + Self::TAG_LEN,
+			LibsodiumError::ApiMisuse("Buffer is too small")
+		);
+		check!(nonce.len() == Self::NONCE_LEN, LibsodiumError::ApiMisuse("Invalid nonce length"));
+		
+		// Call libsodium
+		assert_eq!(sodium!(
+			buf.as_mut_ptr(), ptr::null_mut::<c_ulonglong>(), buf.as_ptr(), plaintext_len,
+			ad.as_ptr(), ad.len(), ptr::null::<c_uchar>(), nonce.as_ptr(), self.state
+				=> crypto_aead_aes256gcm_encrypt_afternm
+		), 0);
+		Ok(plaintext_len + Self::TAG_LEN)
+	}
+	/// Opens the first `ciphertext_len` bytes in `buf` in place; returns the plaintext length
+	pub fn open(&self, buf: &mut[u8], ciphertext_len: usize, ad: &[u8], nonce: &[u8])
+		-> Result<usize, Box<dyn Error>>
+	{
+		// Check parameters
+		check!(buf.len() >= ciphertext_len, LibsodiumError::ApiMisuse("Buffer is too small"));
+		check!(nonce.len() == Self::NONCE_LEN, LibsodiumError::ApiMisuse("Invalid nonce length"));
+		
+		// Call libsodium
+		let result = sodium!(
+			buf.as_mut_ptr(), ptr::null_mut::<c_ulonglong>(), ptr::null_mut::<c_uchar>(),
+			buf.as_ptr(), ciphertext_len, ad.as_ptr(), ad.len(), nonce.as_ptr(), self.state
+				=> crypto_aead_aes256gcm_decrypt_afternm
+		);
+		check!(result == 0, LibsodiumError::InvalidData);
+		Ok(ciphertext_len - Self::TAG_LEN)
+	}
+	
+	/// Seals the first `plaintext_len` bytes in `buf` in place and writes the authentication tag
+	/// into `tag`; returns the ciphertext length (which is always equal to `plaintext_len`)
+	pub fn seal_detached(&self, buf: &mut[u8], plaintext_len: usize, tag: &mut[u8], ad: &[u8],
+		nonce: &[u8]) -> Result<usize, Box<dyn Error>>
+	{
+		// Check parameters
+		check!(buf.len() >= plaintext_len, LibsodiumError::ApiMisuse("Buffer is too small"));
+		check!(tag.len() >= Self::TAG_LEN, LibsodiumError::ApiMisuse("Tag buffer is too small"));
+		check!(nonce.len() == Self::NONCE_LEN, LibsodiumError::ApiMisuse("Invalid nonce length"));
+		
+		// Call libsodium
+		assert_eq!(sodium!(
+			buf.as_mut_ptr(), tag.as_mut_ptr(), ptr::null_mut::<c_ulonglong>(),
+			buf.as_ptr(), plaintext_len, ad.as_ptr(), ad.len(),
+			ptr::null::<c_uchar>(), nonce.as_ptr(), self.state
+				=> crypto_aead_aes256gcm_encrypt_detached_afternm
+		), 0);
+		Ok(plaintext_len)
+	}
+	/// Opens the first `ciphertext_len` bytes in `buf` in place using the authentication tag in
+	/// `tag`; returns the plaintext length (which is always equal to `ciphertext_len`)
+	pub fn open_detached(&self, buf: &mut[u8], ciphertext_len: usize, tag: &[u8], ad: &[u8],
+		nonce: &[u8]) -> Result<usize, Box<dyn Error>>
+	{
+		// Check parameters
+		check!(buf.len() >= ciphertext_len, LibsodiumError::ApiMisuse("Buffer is too small"));
+		check!(tag.len() == Self::TAG_LEN, LibsodiumError::ApiMisuse("Invalid tag length"));
+		check!(nonce.len() == Self::NONCE_LEN, LibsodiumError::ApiMisuse("Invalid nonce length"));
+		
+		// Call libsodium
+		let result = sodium!(
+			buf.as_mut_ptr(), ptr::null_mut::<c_uchar>(), buf.as_ptr(), ciphertext_len,
+			tag.as_ptr(), ad.as_ptr(), ad.len(), nonce.as_ptr(), self.state
+				=> crypto_aead_aes256gcm_decrypt_detached_afternm
+		);
+		check!(result == 0, LibsodiumError::InvalidData);
+		Ok(ciphertext_len)
+	}
+}
+impl Drop for Aes256GcmState {
+	fn drop(&mut self) {
+		// `sodium_free` zeroes the memory before releasing it
+		sodium!(self.state => sodium_free);
+	}
+}
+unsafe impl Send for Aes256GcmState {}
+unsafe impl Sync for Aes256GcmState {}
+
+
+/// An AEAD cipher that stores the authentication tag separately from the ciphertext
+pub trait DetachedAeadCipher: AeadCipher {
+	/// Seals the first `plaintext_len` bytes in `buf` in place and writes the authentication tag
+	/// into `tag`; returns the ciphertext length (which is always equal to `plaintext_len`)
+	fn seal_detached(&self, buf: &mut[u8], plaintext_len: usize, tag: &mut[u8], ad: &[u8],
+		key: &[u8], nonce: &[u8]) -> Result<usize, Box<dyn Error>>;
+	
+	/// Opens the first `ciphertext_len` bytes in `buf` in place using the authentication tag in
+	/// `tag`; returns the plaintext length (which is always equal to `ciphertext_len`)
+	fn open_detached(&self, buf: &mut[u8], ciphertext_len: usize, tag: &[u8], ad: &[u8],
+		key: &[u8], nonce: &[u8]) -> Result<usize, Box<dyn Error>>;
+}
+
+
+/// A stream cipher that can start at an arbitrary byte offset in the keystream
+pub trait SeekableCipher: Cipher {
+	/// Encrypts the first `plaintext_len` bytes in `buf` in place as if they were located at byte
+	/// `offset` of a contiguously encrypted stream; returns the ciphertext length
+	fn encrypt_at(&self, buf: &mut[u8], plaintext_len: usize, key: &[u8], nonce: &[u8],
+		offset: u64) -> Result<usize, Box<dyn Error>>;
+	
+	/// Decrypts the first `ciphertext_len` bytes in `buf` in place as if they were located at byte
+	/// `offset` of a contiguously encrypted stream; returns the plaintext length
+	fn decrypt_at(&self, buf: &mut[u8], ciphertext_len: usize, key: &[u8], nonce: &[u8],
+		offset: u64) -> Result<usize, Box<dyn Error>>;
+}
+
+
 /// Cipher implementations
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Ciphers {
@@ -197,8 +905,48 @@ pub enum Ciphers {
 	Aes256Gcm,
 	/// [ChaCha20+Poly1305 (IETF-version, RFC 7539)](https://tools.ietf.org/html/rfc7539)
 	ChaCha20Poly1305Ietf,
+	/// [XChaCha20+Poly1305 (IETF-version)](https://tools.ietf.org/html/draft-irtf-cfrg-xchacha-03)
+	XChaCha20Poly1305Ietf,
+	/// [ChaCha20+Poly1305 (original version)](https://tools.ietf.org/html/draft-agl-tls-chacha20poly1305-04)
+	ChaCha20Poly1305,
+	/// Key-committing AES-256-GCM
+	///
+	/// _Note: the key and the commitment are derived from the key and nonce with BLAKE2b; the
+	/// 32 byte commitment is prepended to the ciphertext_
+	Aes256GcmCommitting,
+	/// Key-committing ChaCha20+Poly1305 (IETF-version)
+	///
+	/// _Note: see `Aes256GcmCommitting`_
+	ChaCha20Poly1305IetfCommitting,
+	/// Key-committing XChaCha20+Poly1305 (IETF-version)
+	///
+	/// _Note: see `Aes256GcmCommitting`_
+	XChaCha20Poly1305IetfCommitting,
+	/// Deterministic, nonce-misuse-resistant XChaCha20-SIV (BLAKE2b-192 as synthetic IV)
+	///
+	/// _Note: the nonce is optional (i.e. it may be empty); without a nonce, equal plaintexts
+	/// with equal associated data result in equal ciphertexts_
+	XChaCha20Siv,
+	/// [XSalsa20+Poly1305 (NaCl secretbox)](https://nacl.cr.yp.to/secretbox.html)
+	///
+	/// _Note: this cipher does not support associated data and is thus only available as `Cipher`_
+	XSalsa20Poly1305,
+	/// [XChaCha20+Poly1305 (secretbox)](https://download.libsodium.org/doc/secret-key_cryptography/secretbox)
+	///
+	/// _Note: this cipher does not support associated data and is thus only available as `Cipher`_
+	XChaCha20Poly1305,
 	/// [ChaCha20 (IETF-version, RFC 7539)](https://tools.ietf.org/html/rfc7539)
-	ChaCha20Ietf
+	ChaCha20Ietf,
+	/// [XChaCha20](https://tools.ietf.org/html/draft-irtf-cfrg-xchacha-03)
+	XChaCha20,
+	/// [XSalsa20](https://cr.yp.to/snuffle/xsalsa-20110204.pdf)
+	XSalsa20,
+	/// [Salsa20](https://cr.yp.to/snuffle/spec.pdf)
+	Salsa20,
+	/// [Salsa20/12](https://cr.yp.to/snuffle/spec.pdf)
+	Salsa2012,
+	/// [Salsa20/8](https://cr.yp.to/snuffle/spec.pdf)
+	Salsa208
 }
 impl Ciphers {
 	/// Selects a cipher implementation from name
@@ -206,49 +954,188 @@ impl Ciphers {
 	/// Currently supported names are:
 	///  - `Aes256Gcm` which maps to Aes256Gcm
 	///  - `ChaCha20Poly1305Ietf` which maps to ChaCha20Poly1305Ietf
+	///  - `XChaCha20Poly1305Ietf` which maps to XChaCha20Poly1305Ietf
+	///  - `ChaCha20Poly1305` which maps to ChaCha20Poly1305
+	///  - `Aes256GcmCommitting` which maps to Aes256GcmCommitting
+	///  - `ChaCha20Poly1305IetfCommitting` which maps to ChaCha20Poly1305IetfCommitting
+	///  - `XChaCha20Poly1305IetfCommitting` which maps to XChaCha20Poly1305IetfCommitting
+	///  - `XChaCha20Siv` which maps to XChaCha20Siv
+	///  - `XSalsa20Poly1305` which maps to XSalsa20Poly1305
+	///  - `XChaCha20Poly1305` which maps to XChaCha20Poly1305
 	///  - `ChaCha20Ietf` which maps to ChaCha20Ietf
+	///  - `XChaCha20` which maps to XChaCha20
+	///  - `XSalsa20` which maps to XSalsa20
+	///  - `Salsa20` which maps to Salsa20
+	///  - `Salsa2012` which maps to Salsa20/12
+	///  - `Salsa208` which maps to Salsa20/8
+	///
+	/// Returns `LibsodiumError::Unsupported` if the name is unknown or if the selected cipher is
+	/// not available on the current hardware (see `is_available`)
 	pub fn from_name(name: &str) -> Result<Self, LibsodiumError> {
-		Ok(match name {
+		let cipher = match name {
 			"Aes256Gcm" => Ciphers::Aes256Gcm,
 			"ChaCha20Poly1305Ietf" => Ciphers::ChaCha20Poly1305Ietf,
+			"XChaCha20Poly1305Ietf" => Ciphers::XChaCha20Poly1305Ietf,
+			"ChaCha20Poly1305" => Ciphers::ChaCha20Poly1305,
+			"Aes256GcmCommitting" => Ciphers::Aes256GcmCommitting,
+			"ChaCha20Poly1305IetfCommitting" => Ciphers::ChaCha20Poly1305IetfCommitting,
+			"XChaCha20Poly1305IetfCommitting" => Ciphers::XChaCha20Poly1305IetfCommitting,
+			"XChaCha20Siv" => Ciphers::XChaCha20Siv,
+			"XSalsa20Poly1305" => Ciphers::XSalsa20Poly1305,
+			"XChaCha20Poly1305" => Ciphers::XChaCha20Poly1305,
 			"ChaCha20Ietf" => Ciphers::ChaCha20Ietf,
+			"XChaCha20" => Ciphers::XChaCha20,
+			"XSalsa20" => Ciphers::XSalsa20,
+			"Salsa20" => Ciphers::Salsa20,
+			"Salsa2012" => Ciphers::Salsa2012,
+			"Salsa208" => Ciphers::Salsa208,
 			_ => return Err(LibsodiumError::Unsupported)
-		})
+		};
+		check!(cipher.is_available(), LibsodiumError::Unsupported);
+		Ok(cipher)
+	}
+	
+	/// Checks if this implementation is usable on the current hardware
+	///
+	/// libsodium's AES-256-GCM implementation requires hardware support (as reported by
+	/// `crypto_aead_aes256gcm_is_available`); all other implementations are always available
+	pub fn is_available(self) -> bool {
+		match self {
+			Ciphers::Aes256Gcm | Ciphers::Aes256GcmCommitting =>
+				sodium!(=> crypto_aead_aes256gcm_is_available) == 1,
+			_ => true
+		}
+	}
+	/// Selects the best AEAD implementation that is available on the current hardware
+	///
+	/// This is `Aes256Gcm` if the hardware supports it and `ChaCha20Poly1305Ietf` otherwise
+	pub fn best_aead() -> Self {
+		match Ciphers::Aes256Gcm.is_available() {
+			true => Ciphers::Aes256Gcm,
+			false => Ciphers::ChaCha20Poly1305Ietf
+		}
 	}
 	
 	/// Creates a new `Cipher`-instance with this implementation
+	///
+	/// _Note: if the implementation is not available on the current hardware, all operations of
+	/// the returned instance will fail with `LibsodiumError::Unsupported`_
 	pub fn cipher(self) -> Box<dyn Cipher> {
 		match self {
-			Ciphers::Aes256Gcm => Box::new(Aead {
+			Ciphers::XSalsa20Poly1305 => Box::new(Secretbox {
 				cipher: self,
-				encrypt: crypto_aead_aes256gcm_encrypt,
-				decrypt: crypto_aead_aes256gcm_decrypt
+				seal: crypto_secretbox_easy,
+				open: crypto_secretbox_open_easy
 			}),
-			Ciphers::ChaCha20Poly1305Ietf => Box::new(Aead {
+			Ciphers::XChaCha20Poly1305 => Box::new(Secretbox {
 				cipher: self,
-				encrypt: crypto_aead_chacha20poly1305_ietf_encrypt,
-				decrypt: crypto_aead_chacha20poly1305_ietf_decrypt
+				seal: crypto_secretbox_xchacha20poly1305_easy,
+				open: crypto_secretbox_xchacha20poly1305_open_easy
 			}),
-			Ciphers::ChaCha20Ietf => Box::new(Xor {
-				cipher: self,
-				xor: crypto_stream_chacha20_ietf_xor
-			})
+			Ciphers::XChaCha20Siv => Box::new(Siv),
+			_ => match (self.committing(), self.aead()) {
+				(Some(committing), _) => Box::new(committing),
+				(None, Some(aead)) => Box::new(aead),
+				(None, None) => Box::new(self.xor().unwrap())
+			}
 		}
 	}
 	/// Creates a new `AeadCipher`-instance with this implementation
 	pub fn aead_cipher(self) -> Result<Box<dyn AeadCipher>, LibsodiumError> {
-		Ok(match self {
-			Ciphers::Aes256Gcm => Box::new(Aead {
+		check!(self.is_available(), LibsodiumError::Unsupported);
+		match (self, self.committing()) {
+			(Ciphers::XChaCha20Siv, _) => Ok(Box::new(Siv)),
+			(_, Some(committing)) => Ok(Box::new(committing)),
+			(_, None) => Ok(Box::new(self.aead().ok_or(LibsodiumError::Unsupported)?))
+		}
+	}
+	/// Creates a new `DetachedAeadCipher`-instance with this implementation
+	pub fn detached_aead_cipher(self) -> Result<Box<dyn DetachedAeadCipher>, LibsodiumError> {
+		check!(self.is_available(), LibsodiumError::Unsupported);
+		Ok(Box::new(self.aead().ok_or(LibsodiumError::Unsupported)?))
+	}
+	
+	/// Creates a new `SeekableCipher`-instance with this implementation
+	///
+	/// Supported are `ChaCha20Ietf`, `XChaCha20`, `XSalsa20` and `Salsa20`
+	pub fn seekable_cipher(self) -> Result<Box<dyn SeekableCipher>, LibsodiumError> {
+		match self.xor() {
+			Some(xor) if xor.xor_ic.is_some() => Ok(Box::new(xor)),
+			_ => Err(LibsodiumError::Unsupported)
+		}
+	}
+	
+	/// Creates the AEAD implementation or returns `None` if this is not an AEAD cipher
+	fn aead(self) -> Option<Aead> {
+		Some(match self {
+			Ciphers::Aes256Gcm => Aead {
 				cipher: self,
 				encrypt: crypto_aead_aes256gcm_encrypt,
-				decrypt: crypto_aead_aes256gcm_decrypt
-			}),
-			Ciphers::ChaCha20Poly1305Ietf => Box::new(Aead {
+				decrypt: crypto_aead_aes256gcm_decrypt,
+				encrypt_detached: crypto_aead_aes256gcm_encrypt_detached,
+				decrypt_detached: crypto_aead_aes256gcm_decrypt_detached
+			},
+			Ciphers::ChaCha20Poly1305Ietf => Aead {
 				cipher: self,
 				encrypt: crypto_aead_chacha20poly1305_ietf_encrypt,
-				decrypt: crypto_aead_chacha20poly1305_ietf_decrypt
-			}),
-			Ciphers::ChaCha20Ietf => return Err(LibsodiumError::Unsupported)
+				decrypt: crypto_aead_chacha20poly1305_ietf_decrypt,
+				encrypt_detached: crypto_aead_chacha20poly1305_ietf_encrypt_detached,
+				decrypt_detached: crypto_aead_chacha20poly1305_ietf_decrypt_detached
+			},
+			Ciphers::XChaCha20Poly1305Ietf => Aead {
+				cipher: self,
+				encrypt: crypto_aead_xchacha20poly1305_ietf_encrypt,
+				decrypt: crypto_aead_xchacha20poly1305_ietf_decrypt,
+				encrypt_detached: crypto_aead_xchacha20poly1305_ietf_encrypt_detached,
+				decrypt_detached: crypto_aead_xchacha20poly1305_ietf_decrypt_detached
+			},
+			Ciphers::ChaCha20Poly1305 => Aead {
+				cipher: self,
+				encrypt: crypto_aead_chacha20poly1305_encrypt,
+				decrypt: crypto_aead_chacha20poly1305_decrypt,
+				encrypt_detached: crypto_aead_chacha20poly1305_encrypt_detached,
+				decrypt_detached: crypto_aead_chacha20poly1305_decrypt_detached
+			},
+			_ => return None
+		})
+	}
+	/// Creates the key-committing AEAD implementation or returns `None` if this is not a
+	/// key-committing cipher
+	fn committing(self) -> Option<Committing> {
+		let inner = match self {
+			Ciphers::Aes256GcmCommitting => Ciphers::Aes256Gcm,
+			Ciphers::ChaCha20Poly1305IetfCommitting => Ciphers::ChaCha20Poly1305Ietf,
+			Ciphers::XChaCha20Poly1305IetfCommitting => Ciphers::XChaCha20Poly1305Ietf,
+			_ => return None
+		};
+		Some(Committing{ cipher: self, aead: inner.aead().unwrap() })
+	}
+	/// Creates the XOR stream cipher implementation or returns `None` if this is not a stream cipher
+	fn xor(self) -> Option<Xor> {
+		Some(match self {
+			Ciphers::ChaCha20Ietf => Xor {
+				cipher: self,
+				xor: crypto_stream_chacha20_ietf_xor,
+				xor_ic: Some(crypto_stream_chacha20_ietf_xor_ic64)
+			},
+			Ciphers::XChaCha20 => Xor {
+				cipher: self,
+				xor: crypto_stream_xchacha20_xor,
+				xor_ic: Some(crypto_stream_xchacha20_xor_ic)
+			},
+			Ciphers::XSalsa20 => Xor {
+				cipher: self,
+				xor: crypto_stream_xsalsa20_xor,
+				xor_ic: Some(crypto_stream_xsalsa20_xor_ic)
+			},
+			Ciphers::Salsa20 => Xor {
+				cipher: self,
+				xor: crypto_stream_salsa20_xor,
+				xor_ic: Some(crypto_stream_salsa20_xor_ic)
+			},
+			Ciphers::Salsa2012 => Xor { cipher: self, xor: crypto_stream_salsa2012_xor, xor_ic: None },
+			Ciphers::Salsa208 => Xor { cipher: self, xor: crypto_stream_salsa208_xor, xor_ic: None },
+			_ => return None
 		})
 	}
 }
\ No newline at end of file
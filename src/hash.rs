@@ -0,0 +1,351 @@
+use crate::{
+	LibsodiumError,
+	sodium_bindings::{
+		sodium_init, sodium_memzero,
+		crypto_generichash_blake2b_state, crypto_generichash_blake2b_salt_personal,
+		crypto_generichash_blake2b_init_salt_personal, crypto_generichash_blake2b_update,
+		crypto_generichash_blake2b_final,
+		crypto_generichash_blake2b_BYTES_MIN, crypto_generichash_blake2b_BYTES_MAX,
+		crypto_generichash_blake2b_KEYBYTES_MIN, crypto_generichash_blake2b_KEYBYTES_MAX,
+		crypto_generichash_blake2b_SALTBYTES, crypto_generichash_blake2b_PERSONALBYTES,
+		
+		crypto_hash_sha256_state, crypto_hash_sha256, crypto_hash_sha256_init,
+		crypto_hash_sha256_update, crypto_hash_sha256_final, crypto_hash_sha256_BYTES,
+		crypto_hash_sha512_state, crypto_hash_sha512, crypto_hash_sha512_init,
+		crypto_hash_sha512_update, crypto_hash_sha512_final, crypto_hash_sha512_BYTES
+	}
+};
+use std::{
+	ptr, mem, error::Error,
+	io::{ self, Write },
+	os::raw::{ c_uchar, c_int, c_ulonglong }
+};
+
+
+/// Information about a hash implementation
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct HashInfo {
+	/// The name
+	pub name: &'static str,
+	
+	/// The default hash length
+	pub hash_len: usize,
+	/// The minimum hash length
+	pub hash_len_min: usize,
+	/// The maximum hash length
+	pub hash_len_max: usize,
+	
+	/// The minimum key length (`0` if the hash does not support keys)
+	pub key_len_min: usize,
+	/// The maximum key length (`0` if the hash does not support keys)
+	pub key_len_max: usize,
+	
+	/// The salt length if the hash supports a salt
+	pub salt_len: Option<usize>,
+	/// The personalization length if the hash supports a personalization
+	pub personal_len: Option<usize>
+}
+
+
+/// Optional parameters for a hash computation
+#[derive(Debug, Default, Eq, PartialEq, Clone, Copy)]
+pub struct HashParams<'a> {
+	/// The key for a keyed hash (MAC)
+	pub key: Option<&'a[u8]>,
+	/// The salt
+	pub salt: Option<&'a[u8]>,
+	/// The personalization (domain separation) string
+	pub personal: Option<&'a[u8]>
+}
+impl<'a> HashParams<'a> {
+	/// Checks that `self` is supported by a hash described by `info`
+	fn check(&self, info: &HashInfo) -> Result<(), LibsodiumError> {
+		if let Some(key) = self.key {
+			check!(info.key_len_max > 0, LibsodiumError::Unsupported);
+			check!(
+				key.len() >= info.key_len_min && key.len() <= info.key_len_max,
+				LibsodiumError::ApiMisuse("Invalid key length")
+			);
+		}
+		if let Some(salt) = self.salt {
+			let salt_len = info.salt_len.ok_or(LibsodiumError::Unsupported)?;
+			check!(salt.len() == salt_len, LibsodiumError::ApiMisuse("Invalid salt length"));
+		}
+		if let Some(personal) = self.personal {
+			let personal_len = info.personal_len.ok_or(LibsodiumError::Unsupported)?;
+			check!(
+				personal.len() == personal_len,
+				LibsodiumError::ApiMisuse("Invalid personalization length")
+			);
+		}
+		Ok(())
+	}
+	
+	/// The key as pointer-length pair (`NULL` and `0` if there is no key)
+	fn key_ptr(&self) -> (*const c_uchar, usize) {
+		self.key.map(|k| (k.as_ptr(), k.len())).unwrap_or((ptr::null(), 0))
+	}
+	/// The salt as pointer (`NULL` if there is no salt)
+	fn salt_ptr(&self) -> *const c_uchar {
+		self.salt.map(|s| s.as_ptr()).unwrap_or(ptr::null())
+	}
+	/// The personalization as pointer (`NULL` if there is no personalization)
+	fn personal_ptr(&self) -> *const c_uchar {
+		self.personal.map(|p| p.as_ptr()).unwrap_or(ptr::null())
+	}
+}
+
+
+/// A hash function
+pub trait Hash {
+	/// Returns information about the hash
+	fn info(&self) -> HashInfo;
+	
+	/// Hashes `data` with `params` into `buf`; the length of `buf` selects the hash length
+	fn hash(&self, buf: &mut[u8], data: &[u8], params: &HashParams) -> Result<(), Box<dyn Error>>;
+	/// Creates a new incremental state that computes a `hash_len` byte hash with `params`
+	fn state(&self, hash_len: usize, params: &HashParams) -> Result<HashState, Box<dyn Error>>;
+}
+
+
+/// A BLAKE2b implementation
+struct Blake2b;
+impl Hash for Blake2b {
+	fn info(&self) -> HashInfo {
+		HashInfo {
+			name: "Blake2b",
+			hash_len: crypto_generichash_blake2b_BYTES_MAX as usize,
+			hash_len_min: crypto_generichash_blake2b_BYTES_MIN as usize,
+			hash_len_max: crypto_generichash_blake2b_BYTES_MAX as usize,
+			key_len_min: crypto_generichash_blake2b_KEYBYTES_MIN as usize,
+			key_len_max: crypto_generichash_blake2b_KEYBYTES_MAX as usize,
+			salt_len: Some(crypto_generichash_blake2b_SALTBYTES as usize),
+			personal_len: Some(crypto_generichash_blake2b_PERSONALBYTES as usize)
+		}
+	}
+	
+	fn hash(&self, buf: &mut[u8], data: &[u8], params: &HashParams) -> Result<(), Box<dyn Error>> {
+		// Check parameters
+		let info = self.info();
+		check!(buf.len() >= info.hash_len_min, LibsodiumError::ApiMisuse("Buffer is too small"));
+		check!(buf.len() <= info.hash_len_max, LibsodiumError::ApiMisuse("Buffer is too large"));
+		params.check(&info)?;
+		
+		// Compute the hash (absent salts and personalizations are treated as all-zero by libsodium)
+		let (key, key_len) = params.key_ptr();
+		assert_eq!(sodium!(
+			buf.as_mut_ptr(), buf.len(), data.as_ptr(), data.len(), key, key_len,
+			params.salt_ptr(), params.personal_ptr() => crypto_generichash_blake2b_salt_personal
+		), 0);
+		Ok(())
+	}
+	
+	fn state(&self, hash_len: usize, params: &HashParams) -> Result<HashState, Box<dyn Error>> {
+		// Check parameters
+		let info = self.info();
+		check!(
+			hash_len >= info.hash_len_min && hash_len <= info.hash_len_max,
+			LibsodiumError::ApiMisuse("Invalid hash length")
+		);
+		params.check(&info)?;
+		
+		// Initialize the state
+		let mut state: crypto_generichash_blake2b_state = unsafe{ mem::zeroed() };
+		let (key, key_len) = params.key_ptr();
+		assert_eq!(sodium!(
+			&mut state as *mut crypto_generichash_blake2b_state, key, key_len, hash_len,
+			params.salt_ptr(), params.personal_ptr() => crypto_generichash_blake2b_init_salt_personal
+		), 0);
+		Ok(HashState{ state: State::Blake2b(state), hash_len })
+	}
+}
+
+
+/// Creates and initializes a SHA2-256 state
+fn sha256_state() -> State {
+	let mut state: crypto_hash_sha256_state = unsafe{ mem::zeroed() };
+	assert_eq!(sodium!(&mut state as *mut crypto_hash_sha256_state => crypto_hash_sha256_init), 0);
+	State::Sha256(state)
+}
+/// Creates and initializes a SHA2-512 state
+fn sha512_state() -> State {
+	let mut state: crypto_hash_sha512_state = unsafe{ mem::zeroed() };
+	assert_eq!(sodium!(&mut state as *mut crypto_hash_sha512_state => crypto_hash_sha512_init), 0);
+	State::Sha512(state)
+}
+
+
+/// A SHA-2 implementation
+struct Sha2 {
+	pub name: &'static str,
+	pub hash_len: usize,
+	
+	pub hash: unsafe extern "C" fn(
+		out: *mut c_uchar, in_: *const c_uchar, inlen: c_ulonglong
+	) -> c_int,
+	pub init: fn() -> State
+}
+impl Hash for Sha2 {
+	fn info(&self) -> HashInfo {
+		HashInfo {
+			name: self.name,
+			hash_len: self.hash_len, hash_len_min: self.hash_len, hash_len_max: self.hash_len,
+			key_len_min: 0, key_len_max: 0,
+			salt_len: None, personal_len: None
+		}
+	}
+	
+	fn hash(&self, buf: &mut[u8], data: &[u8], params: &HashParams) -> Result<(), Box<dyn Error>> {
+		// Check parameters
+		let info = self.info();
+		check!(buf.len() >= info.hash_len_min, LibsodiumError::ApiMisuse("Buffer is too small"));
+		check!(buf.len() <= info.hash_len_max, LibsodiumError::ApiMisuse("Buffer is too large"));
+		params.check(&info)?;
+		
+		// Compute the hash
+		assert_eq!(sodium!(buf.as_mut_ptr(), data.as_ptr(), data.len() => self.hash), 0);
+		Ok(())
+	}
+	
+	fn state(&self, hash_len: usize, params: &HashParams) -> Result<HashState, Box<dyn Error>> {
+		// Check parameters
+		let info = self.info();
+		check!(hash_len == info.hash_len, LibsodiumError::ApiMisuse("Invalid hash length"));
+		params.check(&info)?;
+		
+		// Initialize the state
+		Ok(HashState{ state: (self.init)(), hash_len })
+	}
+}
+
+
+/// The underlying libsodium state
+enum State {
+	Blake2b(crypto_generichash_blake2b_state),
+	Sha256(crypto_hash_sha256_state),
+	Sha512(crypto_hash_sha512_state)
+}
+
+
+/// An incremental hash state created by `Hash::state`
+///
+/// _Note: the state implements `Write`, so e.g. a file can be hashed with `io::copy`_
+pub struct HashState {
+	state: State,
+	hash_len: usize
+}
+impl HashState {
+	/// The length of the hash that is computed by this state
+	pub fn hash_len(&self) -> usize {
+		self.hash_len
+	}
+	
+	/// Adds `data` to the message
+	pub fn update(&mut self, data: &[u8]) {
+		match &mut self.state {
+			State::Blake2b(state) => assert_eq!(sodium!(
+				state as *mut crypto_generichash_blake2b_state, data.as_ptr(), data.len()
+					=> crypto_generichash_blake2b_update
+			), 0),
+			State::Sha256(state) => assert_eq!(sodium!(
+				state as *mut crypto_hash_sha256_state, data.as_ptr(), data.len()
+					=> crypto_hash_sha256_update
+			), 0),
+			State::Sha512(state) => assert_eq!(sodium!(
+				state as *mut crypto_hash_sha512_state, data.as_ptr(), data.len()
+					=> crypto_hash_sha512_update
+			), 0)
+		}
+	}
+	
+	/// Computes the hash over the message and writes it into `buf`; returns the hash length
+	pub fn finish(mut self, buf: &mut[u8]) -> Result<usize, Box<dyn Error>> {
+		// Check the buffer length
+		check!(buf.len() >= self.hash_len, LibsodiumError::ApiMisuse("Buffer is too small"));
+		
+		// Finalize the hash
+		match &mut self.state {
+			State::Blake2b(state) => assert_eq!(sodium!(
+				state as *mut crypto_generichash_blake2b_state, buf.as_mut_ptr(), self.hash_len
+					=> crypto_generichash_blake2b_final
+			), 0),
+			State::Sha256(state) => assert_eq!(sodium!(
+				state as *mut crypto_hash_sha256_state, buf.as_mut_ptr() => crypto_hash_sha256_final
+			), 0),
+			State::Sha512(state) => assert_eq!(sodium!(
+				state as *mut crypto_hash_sha512_state, buf.as_mut_ptr() => crypto_hash_sha512_final
+			), 0)
+		}
+		Ok(self.hash_len)
+	}
+}
+impl Write for HashState {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.update(buf);
+		Ok(buf.len())
+	}
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+impl Drop for HashState {
+	fn drop(&mut self) {
+		match &mut self.state {
+			State::Blake2b(state) => sodium!(
+				state as *mut crypto_generichash_blake2b_state,
+				mem::size_of::<crypto_generichash_blake2b_state>() => sodium_memzero
+			),
+			State::Sha256(state) => sodium!(
+				state as *mut crypto_hash_sha256_state,
+				mem::size_of::<crypto_hash_sha256_state>() => sodium_memzero
+			),
+			State::Sha512(state) => sodium!(
+				state as *mut crypto_hash_sha512_state,
+				mem::size_of::<crypto_hash_sha512_state>() => sodium_memzero
+			)
+		};
+	}
+}
+
+
+/// Hash implementations
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Hashes {
+	/// [BLAKE2b](https://tools.ietf.org/html/rfc7693) with optional key, salt and personalization
+	Blake2b,
+	/// [SHA2-256](https://tools.ietf.org/html/rfc6234)
+	Sha256,
+	/// [SHA2-512](https://tools.ietf.org/html/rfc6234)
+	Sha512
+}
+impl Hashes {
+	/// Selects a hash implementation from name
+	///
+	/// Currently supported names are:
+	///  - `Blake2b` which maps to BLAKE2b
+	///  - `Sha256` which maps to SHA2-256
+	///  - `Sha512` which maps to SHA2-512
+	pub fn from_name(name: &str) -> Result<Self, LibsodiumError> {
+		Ok(match name {
+			"Blake2b" => Hashes::Blake2b,
+			"Sha256" => Hashes::Sha256,
+			"Sha512" => Hashes::Sha512,
+			_ => return Err(LibsodiumError::Unsupported)
+		})
+	}
+	
+	/// Creates a new `Hash`-instance with this implementation
+	pub fn hash(self) -> Box<dyn Hash> {
+		match self {
+			Hashes::Blake2b => Box::new(Blake2b),
+			Hashes::Sha256 => Box::new(Sha2 {
+				name: "Sha256", hash_len: crypto_hash_sha256_BYTES as usize,
+				hash: crypto_hash_sha256, init: sha256_state
+			}),
+			Hashes::Sha512 => Box::new(Sha2 {
+				name: "Sha512", hash_len: crypto_hash_sha512_BYTES as usize,
+				hash: crypto_hash_sha512, init: sha512_state
+			})
+		}
+	}
+}
@@ -20,13 +20,22 @@
 // Mods
 mod sodium_bindings;
 pub mod cipher;
+pub mod hash;
+pub mod nonce;
 pub mod pbkdf;
 pub mod rng;
+pub mod secretstream;
+pub mod session;
+pub mod shorthash;
 pub mod signer;
+pub mod stream;
 
 
 // Uses and reexports
-pub use crate::{ cipher::Ciphers, pbkdf::Pbkdfs, rng::SystemRng, signer::Signers };
+pub use crate::{
+	cipher::Ciphers, hash::Hashes, pbkdf::Pbkdfs, rng::SystemRng, shorthash::SodiumSipHasher,
+	signer::Signers
+};
 pub use crypto_api;
 use std::{ error::Error, fmt::{ Display, Formatter, Result as FmtResult } };
 
@@ -43,7 +52,9 @@ pub enum LibsodiumError {
 	/// The operation may be valid but not in this state
 	InvalidState,
 	/// Unsupported algorithm/parameter/etc.
-	Unsupported
+	Unsupported,
+	/// The data ended unexpectedly (e.g. a stream without final tag)
+	Truncated
 }
 impl Display for LibsodiumError {
 	fn fmt(&self, f: &mut Formatter) -> FmtResult {
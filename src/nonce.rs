@@ -0,0 +1,128 @@
+use crate::{
+	LibsodiumError, SystemRng,
+	sodium_bindings::{ sodium_init, sodium_increment }
+};
+use std::error::Error;
+use crypto_api::{ rng::SecureRng, cipher::AeadCipher };
+
+
+/// A sequence of nonces
+pub trait NonceSequence {
+	/// The length of the generated nonces
+	fn nonce_len(&self) -> usize;
+	
+	/// Writes the next nonce into `buf`; returns the nonce length
+	///
+	/// Returns `LibsodiumError::ResourceError` if the sequence is exhausted (i.e. the next nonce
+	/// would repeat a previous one)
+	fn next_nonce(&mut self, buf: &mut[u8]) -> Result<usize, LibsodiumError>;
+}
+
+
+/// A monotonically increasing little-endian counter nonce
+///
+/// This is safe for any nonce length as long as the key is not used with another sequence.
+pub struct CounterNonce {
+	next: Vec<u8>,
+	start: Vec<u8>,
+	exhausted: bool
+}
+impl CounterNonce {
+	/// Creates a new counter that starts at zero
+	pub fn new(nonce_len: usize) -> Self {
+		Self::with_start(&vec![0; nonce_len])
+	}
+	/// Creates a new counter that starts at `start`
+	///
+	/// The sequence is exhausted if it would wrap around to `start` again
+	pub fn with_start(start: &[u8]) -> Self {
+		Self{ next: start.to_vec(), start: start.to_vec(), exhausted: false }
+	}
+}
+impl NonceSequence for CounterNonce {
+	fn nonce_len(&self) -> usize {
+		self.next.len()
+	}
+	
+	fn next_nonce(&mut self, buf: &mut[u8]) -> Result<usize, LibsodiumError> {
+		// Check the state and parameters
+		let nonce_len = self.nonce_len();
+		check!(!self.exhausted, LibsodiumError::ResourceError);
+		check!(buf.len() >= nonce_len, LibsodiumError::ApiMisuse("Buffer is too small"));
+		
+		// Copy the current nonce and increment the counter
+		buf[..nonce_len].copy_from_slice(&self.next);
+		sodium!(self.next.as_mut_ptr(), nonce_len => sodium_increment);
+		self.exhausted = self.next == self.start;
+		Ok(nonce_len)
+	}
+}
+
+
+/// A random nonce
+///
+/// _Note: random nonces are only safe if the nonce is long enough to make collisions unlikely;
+/// this is the case for 24 byte nonces (e.g. `XChaCha20Poly1305Ietf`) but 12 byte nonces should
+/// not be used for more than 2^32 messages per key._
+pub struct RandomNonce {
+	nonce_len: usize
+}
+impl RandomNonce {
+	/// Creates a new random nonce generator
+	pub fn new(nonce_len: usize) -> Self {
+		Self{ nonce_len }
+	}
+}
+impl NonceSequence for RandomNonce {
+	fn nonce_len(&self) -> usize {
+		self.nonce_len
+	}
+	
+	fn next_nonce(&mut self, buf: &mut[u8]) -> Result<usize, LibsodiumError> {
+		check!(buf.len() >= self.nonce_len, LibsodiumError::ApiMisuse("Buffer is too small"));
+		SystemRng.random(&mut buf[..self.nonce_len]).map_err(|_| LibsodiumError::ResourceError)?;
+		Ok(self.nonce_len)
+	}
+}
+
+
+/// Seals the first `plaintext_len` bytes in `buf` in place using the next nonce from `nonces` and
+/// prepends the nonce; returns the length of `nonce || ciphertext`
+pub fn seal(cipher: &dyn AeadCipher, nonces: &mut dyn NonceSequence, buf: &mut[u8],
+	plaintext_len: usize, ad: &[u8], key: &[u8]) -> Result<usize, Box<dyn Error>>
+{
+	// Check parameters
+	let nonce_len = cipher.info().nonce_len;
+	check!(nonces.nonce_len() == nonce_len, LibsodiumError::ApiMisuse("Invalid nonce length"));
+	check!(
+		buf.len() >= nonce_len + cipher.encrypted_len_max(plaintext_len),
+		LibsodiumError::ApiMisuse("Buffer is too small")
+	);
+	
+	// Draw the nonce first so that `buf` remains untouched if the sequence is exhausted
+	let mut nonce = vec![0; nonce_len];
+	nonces.next_nonce(&mut nonce)?;
+	
+	// Move the plaintext behind the nonce and seal it
+	buf.copy_within(..plaintext_len, nonce_len);
+	buf[..nonce_len].copy_from_slice(&nonce);
+	let (nonce, buf) = buf.split_at_mut(nonce_len);
+	let ciphertext_len = cipher.seal(buf, plaintext_len, ad, key, nonce)?;
+	Ok(nonce_len + ciphertext_len)
+}
+/// Opens the first `len` bytes in `buf` in place that have been created by `seal`; returns the
+/// plaintext length
+pub fn open(cipher: &dyn AeadCipher, buf: &mut[u8], len: usize, ad: &[u8], key: &[u8])
+	-> Result<usize, Box<dyn Error>>
+{
+	// Check parameters
+	let nonce_len = cipher.info().nonce_len;
+	check!(buf.len() >= len, LibsodiumError::ApiMisuse("Buffer is too small"));
+	check!(len >= nonce_len, LibsodiumError::InvalidData);
+	
+	// Open the ciphertext and move the plaintext to the beginning of the buffer
+	let (nonce, ciphertext) = buf.split_at_mut(nonce_len);
+	let plaintext_len = cipher.open(ciphertext, len - nonce_len, ad, key, nonce)?;
+	buf.copy_within(nonce_len..nonce_len + plaintext_len, 0);
+	Ok(plaintext_len)
+}
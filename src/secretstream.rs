@@ -0,0 +1,429 @@
+use crate::{
+	LibsodiumError,
+	sodium_bindings::{
+		sodium_init, sodium_memzero, crypto_secretstream_xchacha20poly1305_state,
+		crypto_secretstream_xchacha20poly1305_init_push, crypto_secretstream_xchacha20poly1305_push,
+		crypto_secretstream_xchacha20poly1305_init_pull, crypto_secretstream_xchacha20poly1305_pull,
+		crypto_secretstream_xchacha20poly1305_rekey,
+		crypto_secretstream_xchacha20poly1305_KEYBYTES,
+		crypto_secretstream_xchacha20poly1305_HEADERBYTES,
+		crypto_secretstream_xchacha20poly1305_ABYTES,
+		crypto_secretstream_xchacha20poly1305_TAG_MESSAGE,
+		crypto_secretstream_xchacha20poly1305_TAG_PUSH,
+		crypto_secretstream_xchacha20poly1305_TAG_REKEY,
+		crypto_secretstream_xchacha20poly1305_TAG_FINAL
+	}
+};
+use std::{
+	ptr, mem, cmp, error::Error,
+	io::{ self, Read, Write },
+	os::raw::{ c_uchar, c_ulonglong }
+};
+
+
+/// The key length
+pub const KEY_LEN: usize = crypto_secretstream_xchacha20poly1305_KEYBYTES as usize;
+/// The length of the stream header
+pub const HEADER_LEN: usize = crypto_secretstream_xchacha20poly1305_HEADERBYTES as usize;
+/// The amount of bytes a chunk grows during encryption
+pub const CHUNK_OVERHEAD: usize = crypto_secretstream_xchacha20poly1305_ABYTES as usize;
+/// The default plaintext length of a chunk used by `EncryptingWriter` and `DecryptingReader`
+pub const DEFAULT_CHUNK_LEN: usize = 64 * 1024;
+
+
+/// A chunk tag
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Tag {
+	/// A normal chunk
+	Message,
+	/// Marks the end of a set of chunks (e.g. a message) but not the end of the stream
+	Push,
+	/// Rekeys the stream after this chunk
+	Rekey,
+	/// Marks the last chunk of the stream and erases the key
+	Final
+}
+impl Tag {
+	/// The raw libsodium tag value
+	fn to_raw(self) -> c_uchar {
+		let tag = match self {
+			Tag::Message => crypto_secretstream_xchacha20poly1305_TAG_MESSAGE,
+			Tag::Push => crypto_secretstream_xchacha20poly1305_TAG_PUSH,
+			Tag::Rekey => crypto_secretstream_xchacha20poly1305_TAG_REKEY,
+			Tag::Final => crypto_secretstream_xchacha20poly1305_TAG_FINAL
+		};
+		tag as c_uchar
+	}
+	/// Parses a raw libsodium tag value
+	fn from_raw(tag: c_uchar) -> Result<Self, LibsodiumError> {
+		[Tag::Message, Tag::Push, Tag::Rekey, Tag::Final].iter().copied()
+			.find(|t| t.to_raw() == tag)
+			.ok_or(LibsodiumError::InvalidData)
+	}
+}
+
+
+/// The state of a stream
+struct State {
+	state: crypto_secretstream_xchacha20poly1305_state,
+	finalized: bool
+}
+impl State {
+	/// Creates a new uninitialized state
+	fn new() -> Self {
+		Self{ state: unsafe{ mem::zeroed() }, finalized: false }
+	}
+	/// Explicitly rekeys the state
+	fn rekey(&mut self) -> Result<(), LibsodiumError> {
+		check!(!self.finalized, LibsodiumError::InvalidState);
+		sodium!(&mut self.state as *mut crypto_secretstream_xchacha20poly1305_state
+			=> crypto_secretstream_xchacha20poly1305_rekey);
+		Ok(())
+	}
+}
+impl Drop for State {
+	fn drop(&mut self) {
+		sodium!(
+			&mut self.state as *mut crypto_secretstream_xchacha20poly1305_state,
+			mem::size_of::<crypto_secretstream_xchacha20poly1305_state>()
+				=> sodium_memzero
+		);
+	}
+}
+
+
+/// The encrypting side of a [XChaCha20-Poly1305 secretstream](https://download.libsodium.org/doc/secret-key_cryptography/secretstream)
+pub struct PushStream {
+	state: State
+}
+impl PushStream {
+	/// Creates a new stream with `key` and writes the stream header into `header`
+	///
+	/// The header must be transmitted to the receiver before the first chunk
+	pub fn new(header: &mut[u8], key: &[u8]) -> Result<Self, LibsodiumError> {
+		// Check parameters
+		check!(header.len() >= HEADER_LEN, LibsodiumError::ApiMisuse("Buffer is too small"));
+		check!(key.len() == KEY_LEN, LibsodiumError::ApiMisuse("Invalid key length"));
+		
+		// Initialize the stream
+		let mut state = State::new();
+		assert_eq!(sodium!(
+			&mut state.state as *mut crypto_secretstream_xchacha20poly1305_state,
+			header.as_mut_ptr(), key.as_ptr()
+				=> crypto_secretstream_xchacha20poly1305_init_push
+		), 0);
+		Ok(Self{ state })
+	}
+	
+	/// Encrypts the first `plaintext_len` bytes in `buf` in place as the next chunk; returns the
+	/// chunk length (which is `plaintext_len + CHUNK_OVERHEAD`)
+	///
+	/// _Note: after a chunk with `Tag::Final` has been pushed, all further operations will fail
+	/// with `LibsodiumError::InvalidState`_
+	pub fn push(&mut self, buf: &mut[u8], plaintext_len: usize, ad: &[u8], tag: Tag)
+		-> Result<usize, Box<dyn Error>>
+	{
+		// Check parameters
+		check!(!self.state.finalized, LibsodiumError::InvalidState);
+		check!(
+			buf.len() >= plaintext_len + CHUNK_OVERHEAD,
+			LibsodiumError::ApiMisuse("Buffer is too small")
+		);
+		
+		// Move the plaintext behind the tag byte so that libsodium can encrypt it in place
+		buf.copy_within(..plaintext_len, 1);
+		assert_eq!(sodium!(
+			&mut self.state.state as *mut crypto_secretstream_xchacha20poly1305_state,
+			buf.as_mut_ptr(), ptr::null_mut::<c_ulonglong>(), buf[1..].as_ptr(), plaintext_len,
+			ad.as_ptr(), ad.len(), tag.to_raw()
+				=> crypto_secretstream_xchacha20poly1305_push
+		), 0);
+		
+		self.state.finalized = tag == Tag::Final;
+		Ok(plaintext_len + CHUNK_OVERHEAD)
+	}
+	
+	/// Explicitly rekeys the stream
+	///
+	/// _Note: the receiver must call `PullStream::rekey` at the same position in the stream_
+	pub fn rekey(&mut self) -> Result<(), LibsodiumError> {
+		self.state.rekey()
+	}
+}
+
+
+/// The decrypting side of a [XChaCha20-Poly1305 secretstream](https://download.libsodium.org/doc/secret-key_cryptography/secretstream)
+pub struct PullStream {
+	state: State
+}
+impl PullStream {
+	/// Creates a new stream with `key` from the stream header in `header`
+	pub fn new(header: &[u8], key: &[u8]) -> Result<Self, LibsodiumError> {
+		// Check parameters
+		check!(header.len() == HEADER_LEN, LibsodiumError::ApiMisuse("Invalid header length"));
+		check!(key.len() == KEY_LEN, LibsodiumError::ApiMisuse("Invalid key length"));
+		
+		// Initialize the stream
+		let mut state = State::new();
+		let result = sodium!(
+			&mut state.state as *mut crypto_secretstream_xchacha20poly1305_state,
+			header.as_ptr(), key.as_ptr()
+				=> crypto_secretstream_xchacha20poly1305_init_pull
+		);
+		check!(result == 0, LibsodiumError::InvalidData);
+		Ok(Self{ state })
+	}
+	
+	/// Decrypts and verifies the first `chunk_len` bytes in `buf` in place as the next chunk;
+	/// returns the plaintext length and the chunk's tag
+	///
+	/// Returns `LibsodiumError::InvalidData` if the chunk is forged, corrupted, reordered or
+	/// belongs to another stream. The state is not modified in this case.
+	pub fn pull(&mut self, buf: &mut[u8], chunk_len: usize, ad: &[u8])
+		-> Result<(usize, Tag), Box<dyn Error>>
+	{
+		// Check parameters
+		check!(!self.state.finalized, LibsodiumError::InvalidState);
+		check!(buf.len() >= chunk_len, LibsodiumError::ApiMisuse("Buffer is too small"));
+		check!(chunk_len >= CHUNK_OVERHEAD, LibsodiumError::InvalidData);
+		
+		// Decrypt the chunk so that the plaintext overlaps with the ciphertext...
+		let mut tag: c_uchar = 0;
+		let result = sodium!(
+			&mut self.state.state as *mut crypto_secretstream_xchacha20poly1305_state,
+			buf[1..].as_mut_ptr(), ptr::null_mut::<c_ulonglong>(), &mut tag as *mut c_uchar,
+			buf.as_ptr(), chunk_len, ad.as_ptr(), ad.len()
+				=> crypto_secretstream_xchacha20poly1305_pull
+		);
+		check!(result == 0, LibsodiumError::InvalidData);
+		
+		// ...and move it to the beginning of the buffer
+		let plaintext_len = chunk_len - CHUNK_OVERHEAD;
+		buf.copy_within(1..plaintext_len + 1, 0);
+		
+		let tag = Tag::from_raw(tag)?;
+		self.state.finalized = tag == Tag::Final;
+		Ok((plaintext_len, tag))
+	}
+	
+	/// Explicitly rekeys the stream
+	///
+	/// _Note: this must be called at the same position in the stream as `PushStream::rekey`_
+	pub fn rekey(&mut self) -> Result<(), LibsodiumError> {
+		self.state.rekey()
+	}
+	
+	/// Whether a chunk with `Tag::Final` has been pulled or not
+	pub fn is_finalized(&self) -> bool {
+		self.state.finalized
+	}
+	/// Consumes the stream and checks that it has been properly finalized
+	///
+	/// Returns `LibsodiumError::Truncated` if no chunk with `Tag::Final` has been pulled (i.e. the
+	/// stream has been truncated)
+	pub fn finish(self) -> Result<(), LibsodiumError> {
+		check!(self.state.finalized, LibsodiumError::Truncated);
+		Ok(())
+	}
+}
+
+
+
+/// Converts an error returned by a stream into an `io::Error`
+fn io_error(kind: io::ErrorKind, error: Box<dyn Error>) -> io::Error {
+	match error.downcast::<LibsodiumError>() {
+		Ok(error) => io::Error::new(kind, *error),
+		Err(error) => io::Error::new(kind, error.to_string())
+	}
+}
+/// A chunk buffer that is erased on drop
+///
+/// _Note: this is a separate type (like `State`) because the adapters move `inner` out of
+/// themselves in `finish`/`into_inner`, which is not possible for types that implement `Drop`_
+struct ChunkBuffer(Vec<u8>);
+impl ChunkBuffer {
+	/// Erases the used part of the buffer
+	fn wipe(&mut self) {
+		sodium!(self.0.as_mut_ptr(), self.0.len() => sodium_memzero);
+	}
+}
+impl Drop for ChunkBuffer {
+	fn drop(&mut self) {
+		self.wipe();
+	}
+}
+
+
+/// Reads until `buf` is full or `source` is exhausted and returns the amount of bytes read
+fn read_full(source: &mut impl Read, buf: &mut[u8]) -> io::Result<usize> {
+	let mut pos = 0;
+	while pos < buf.len() {
+		match source.read(&mut buf[pos..]) {
+			Ok(0) => break,
+			Ok(read) => pos += read,
+			Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+			Err(e) => return Err(e)
+		}
+	}
+	Ok(pos)
+}
+
+
+/// A writer that encrypts all data into a secretstream
+///
+/// The output consists of the stream header followed by chunks of `chunk_len` plaintext bytes
+/// (plus `CHUNK_OVERHEAD`); the last chunk is marked with `Tag::Final` and may be shorter. The
+/// reader must use the same `chunk_len`.
+///
+/// _Note: you must call `finish` after the last write – otherwise the stream is incomplete and will
+/// be rejected as truncated. If an error occurs, the writer must not be used anymore._
+pub struct EncryptingWriter<W: Write> {
+	inner: W,
+	stream: PushStream,
+	buf: ChunkBuffer,
+	chunk_len: usize
+}
+impl<W: Write> EncryptingWriter<W> {
+	/// Creates a new writer with `DEFAULT_CHUNK_LEN` and writes the stream header to `inner`
+	pub fn new(inner: W, key: &[u8]) -> io::Result<Self> {
+		Self::with_chunk_len(inner, key, DEFAULT_CHUNK_LEN)
+	}
+	/// Creates a new writer with `chunk_len` and writes the stream header to `inner`
+	pub fn with_chunk_len(mut inner: W, key: &[u8], chunk_len: usize) -> io::Result<Self> {
+		// Check parameters
+		if chunk_len == 0 {
+			let error = LibsodiumError::ApiMisuse("Invalid chunk length");
+			return Err(io::Error::new(io::ErrorKind::InvalidInput, error));
+		}
+		
+		// Create the stream and write the header
+		let mut header = [0; HEADER_LEN];
+		let stream = PushStream::new(&mut header, key)
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+		inner.write_all(&header)?;
+		
+		let buf = ChunkBuffer(Vec::with_capacity(chunk_len + CHUNK_OVERHEAD));
+		Ok(Self{ inner, stream, buf, chunk_len })
+	}
+	
+	/// Encrypts the buffered plaintext as chunk with `tag` and writes it to `inner`
+	fn push_chunk(&mut self, tag: Tag) -> io::Result<()> {
+		let plaintext_len = self.buf.0.len();
+		self.buf.0.resize(plaintext_len + CHUNK_OVERHEAD, 0);
+		self.stream.push(&mut self.buf.0, plaintext_len, &[], tag)
+			.map_err(|e| io_error(io::ErrorKind::Other, e))?;
+		
+		self.inner.write_all(&self.buf.0)?;
+		self.buf.wipe();
+		self.buf.0.clear();
+		Ok(())
+	}
+	
+	/// Writes the final chunk, flushes `inner` and returns it
+	pub fn finish(mut self) -> io::Result<W> {
+		self.push_chunk(Tag::Final)?;
+		self.inner.flush()?;
+		Ok(self.inner)
+	}
+}
+impl<W: Write> Write for EncryptingWriter<W> {
+	fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+		// Only emit a full chunk if there is more data (the last chunk is emitted by `finish`)
+		if self.buf.0.len() == self.chunk_len && !data.is_empty() {
+			self.push_chunk(Tag::Message)?;
+		}
+		
+		let to_copy = cmp::min(data.len(), self.chunk_len - self.buf.0.len());
+		self.buf.0.extend_from_slice(&data[..to_copy]);
+		Ok(to_copy)
+	}
+	/// Flushes `inner`
+	///
+	/// _Note: this does not emit the currently buffered plaintext because all chunks but the last
+	/// one must be complete_
+	fn flush(&mut self) -> io::Result<()> {
+		self.inner.flush()
+	}
+}
+
+
+/// A reader that decrypts and verifies a secretstream created by `EncryptingWriter`
+///
+/// Forged, corrupted or reordered chunks and trailing data after the final chunk are reported as
+/// `io::ErrorKind::InvalidData`; a stream that ends without final chunk is reported as
+/// `io::ErrorKind::UnexpectedEof`. The underlying `LibsodiumError` is available via
+/// `io::Error::get_ref`.
+pub struct DecryptingReader<R: Read> {
+	inner: R,
+	stream: PullStream,
+	buf: ChunkBuffer,
+	pos: usize,
+	len: usize
+}
+impl<R: Read> DecryptingReader<R> {
+	/// Creates a new reader with `DEFAULT_CHUNK_LEN` and reads the stream header from `inner`
+	pub fn new(inner: R, key: &[u8]) -> io::Result<Self> {
+		Self::with_chunk_len(inner, key, DEFAULT_CHUNK_LEN)
+	}
+	/// Creates a new reader with `chunk_len` and reads the stream header from `inner`
+	pub fn with_chunk_len(mut inner: R, key: &[u8], chunk_len: usize) -> io::Result<Self> {
+		// Check parameters
+		if chunk_len == 0 {
+			let error = LibsodiumError::ApiMisuse("Invalid chunk length");
+			return Err(io::Error::new(io::ErrorKind::InvalidInput, error));
+		}
+		
+		// Read the header and create the stream
+		let mut header = [0; HEADER_LEN];
+		if read_full(&mut inner, &mut header)? != HEADER_LEN {
+			return Err(io::Error::new(io::ErrorKind::UnexpectedEof, LibsodiumError::Truncated));
+		}
+		let stream = PullStream::new(&header, key)
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+		
+		let buf = ChunkBuffer(vec![0; chunk_len + CHUNK_OVERHEAD]);
+		Ok(Self{ inner, stream, buf, pos: 0, len: 0 })
+	}
+	
+	/// Reads, decrypts and verifies the next chunk
+	fn pull_chunk(&mut self) -> io::Result<()> {
+		// Read the next chunk
+		let chunk_len = read_full(&mut self.inner, &mut self.buf.0)?;
+		if chunk_len == 0 {
+			return Err(io::Error::new(io::ErrorKind::UnexpectedEof, LibsodiumError::Truncated));
+		}
+		
+		// Decrypt the chunk
+		let (plaintext_len, tag) = self.stream.pull(&mut self.buf.0, chunk_len, &[])
+			.map_err(|e| io_error(io::ErrorKind::InvalidData, e))?;
+		self.pos = 0;
+		self.len = plaintext_len;
+		
+		// Ensure that there is no trailing data after the final chunk
+		if tag == Tag::Final && read_full(&mut self.inner, &mut [0])? != 0 {
+			return Err(io::Error::new(io::ErrorKind::InvalidData, LibsodiumError::InvalidData));
+		}
+		Ok(())
+	}
+	
+	/// Returns the underlying reader
+	pub fn into_inner(self) -> R {
+		self.inner
+	}
+}
+impl<R: Read> Read for DecryptingReader<R> {
+	fn read(&mut self, buf: &mut[u8]) -> io::Result<usize> {
+		// Pull chunks until we have some plaintext or the stream is finalized
+		while self.pos == self.len {
+			match self.stream.is_finalized() {
+				true => return Ok(0),
+				false => self.pull_chunk()?
+			}
+		}
+		
+		let to_copy = cmp::min(buf.len(), self.len - self.pos);
+		buf[..to_copy].copy_from_slice(&self.buf.0[self.pos..self.pos + to_copy]);
+		self.pos += to_copy;
+		Ok(to_copy)
+	}
+}
@@ -0,0 +1,245 @@
+use crate::{
+	LibsodiumError, Ciphers,
+	sodium_bindings::{ sodium_init, sodium_memzero, crypto_kdf_derive_from_key, crypto_kdf_KEYBYTES }
+};
+use std::{ mem, error::Error, convert::TryInto };
+use crypto_api::cipher::AeadCipher;
+
+
+/// The key length
+pub const KEY_LEN: usize = crypto_kdf_KEYBYTES as usize;
+/// The length of the message header (`epoch (u32 LE) || sequence number (u64 LE)`)
+pub const HEADER_LEN: usize = 12;
+/// The amount of sequence numbers below the highest received one that are still accepted
+pub const REPLAY_WINDOW: u64 = 64;
+/// The maximum amount of epochs the receiver derives forward at once
+pub const MAX_EPOCH_SKIP: u32 = 64;
+
+/// The KDF context used to derive the next epoch key
+const KDF_CONTEXT: &[u8; 8] = b"AeadRkey";
+
+
+/// The thresholds after which a session switches to the next key
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct RekeyPolicy {
+	/// The maximum amount of messages sealed with one key
+	pub max_messages: u64,
+	/// The maximum amount of plaintext bytes sealed with one key
+	pub max_bytes: u64
+}
+impl Default for RekeyPolicy {
+	fn default() -> Self {
+		Self{ max_messages: 1 << 24, max_bytes: 1 << 36 }
+	}
+}
+
+
+/// A key epoch
+struct Epoch {
+	number: u32,
+	key: [u8; KEY_LEN],
+	highest: Option<u64>,
+	seen: u64
+}
+impl Epoch {
+	/// Creates the first epoch from `key`
+	fn new(key: &[u8]) -> Result<Self, LibsodiumError> {
+		check!(key.len() == KEY_LEN, LibsodiumError::ApiMisuse("Invalid key length"));
+		
+		let mut epoch = Self{ number: 0, key: [0; KEY_LEN], highest: None, seen: 0 };
+		epoch.key.copy_from_slice(key);
+		Ok(epoch)
+	}
+	/// Derives the next epoch as `KDF(key, id = number + 1, ctx = "AeadRkey")`
+	fn next(&self) -> Result<Self, LibsodiumError> {
+		let number = self.number.checked_add(1).ok_or(LibsodiumError::ResourceError)?;
+		let mut epoch = Self{ number, key: [0; KEY_LEN], highest: None, seen: 0 };
+		assert_eq!(sodium!(
+			epoch.key.as_mut_ptr(), KEY_LEN, u64::from(number), KDF_CONTEXT.as_ptr(), self.key.as_ptr()
+				=> crypto_kdf_derive_from_key
+		), 0);
+		Ok(epoch)
+	}
+	
+	/// Checks that `seq` is neither replayed nor outside of the window
+	fn check(&self, seq: u64) -> Result<(), LibsodiumError> {
+		match self.highest {
+			Some(highest) if seq <= highest => {
+				check!(highest - seq < REPLAY_WINDOW, LibsodiumError::InvalidData);
+				check!(self.seen & (1 << (highest - seq)) == 0, LibsodiumError::InvalidData);
+				Ok(())
+			},
+			_ => Ok(())
+		}
+	}
+	/// Marks `seq` as received
+	fn mark(&mut self, seq: u64) {
+		self.seen = match self.highest {
+			Some(highest) if seq <= highest => self.seen | 1 << (highest - seq),
+			Some(highest) if seq - highest < REPLAY_WINDOW => self.seen << (seq - highest) | 1,
+			_ => 1
+		};
+		self.highest = Some(self.highest.map_or(seq, |h| h.max(seq)));
+	}
+}
+impl Drop for Epoch {
+	fn drop(&mut self) {
+		sodium!(self.key.as_mut_ptr(), KEY_LEN => sodium_memzero);
+	}
+}
+
+
+/// Creates the AEAD cipher for a session
+fn session_cipher(cipher: Ciphers) -> Result<Box<dyn AeadCipher>, LibsodiumError> {
+	let aead = cipher.aead_cipher()?;
+	check!(aead.info().key_len == KEY_LEN, LibsodiumError::Unsupported);
+	check!(aead.info().nonce_len >= HEADER_LEN, LibsodiumError::Unsupported);
+	Ok(aead)
+}
+/// Creates the nonce for a message header
+fn nonce(header: &[u8], nonce_len: usize) -> Vec<u8> {
+	let mut nonce = header[..HEADER_LEN].to_vec();
+	nonce.resize(nonce_len, 0);
+	nonce
+}
+
+
+/// The sealing side of a long-lived AEAD session that automatically switches to the next key
+/// according to a `RekeyPolicy`
+///
+/// Each message is prefixed with a header that contains the key epoch and the message's sequence
+/// number; the header (zero-padded) is also used as nonce.
+pub struct SealingSession {
+	cipher: Box<dyn AeadCipher>,
+	policy: RekeyPolicy,
+	epoch: Epoch,
+	seq: u64,
+	bytes: u64
+}
+impl SealingSession {
+	/// Creates a new session with `cipher` and the initial `key`
+	///
+	/// _Note: `cipher` must be an AEAD cipher with a 32 byte key and a nonce of at least 12 bytes;
+	/// otherwise `LibsodiumError::Unsupported` is returned_
+	pub fn new(cipher: Ciphers, key: &[u8], policy: RekeyPolicy) -> Result<Self, LibsodiumError> {
+		Ok(Self{ cipher: session_cipher(cipher)?, policy, epoch: Epoch::new(key)?, seq: 0, bytes: 0 })
+	}
+	
+	/// The current key epoch
+	pub fn epoch(&self) -> u32 {
+		self.epoch.number
+	}
+	
+	/// Seals the first `plaintext_len` bytes in `buf` in place and prepends the header; returns
+	/// the message length (which is `HEADER_LEN + plaintext_len + overhead`)
+	///
+	/// If the policy's thresholds would be exceeded, the session switches to the next key first
+	pub fn seal(&mut self, buf: &mut[u8], plaintext_len: usize, ad: &[u8])
+		-> Result<usize, Box<dyn Error>>
+	{
+		// Check parameters
+		check!(
+			buf.len() >= HEADER_LEN + self.cipher.encrypted_len_max(plaintext_len),
+			LibsodiumError::ApiMisuse("Buffer is too small")
+		);
+		
+		// Switch to the next key if necessary (a key is always used for at least one message)
+		let bytes = self.bytes.saturating_add(plaintext_len as u64);
+		if self.seq > 0 && (self.seq >= self.policy.max_messages || bytes > self.policy.max_bytes) {
+			self.rekey()?;
+		}
+		
+		// Write the header and seal the message
+		buf.copy_within(..plaintext_len, HEADER_LEN);
+		buf[..4].copy_from_slice(&self.epoch.number.to_le_bytes());
+		buf[4..HEADER_LEN].copy_from_slice(&self.seq.to_le_bytes());
+		
+		let nonce = nonce(buf, self.cipher.info().nonce_len);
+		let ciphertext_len = self.cipher.seal(
+			&mut buf[HEADER_LEN..], plaintext_len, ad, &self.epoch.key, &nonce
+		)?;
+		
+		self.seq += 1;
+		self.bytes = self.bytes.saturating_add(plaintext_len as u64);
+		Ok(HEADER_LEN + ciphertext_len)
+	}
+	
+	/// Explicitly switches to the next key
+	pub fn rekey(&mut self) -> Result<(), LibsodiumError> {
+		self.epoch = self.epoch.next()?;
+		self.seq = 0;
+		self.bytes = 0;
+		Ok(())
+	}
+}
+
+
+/// The opening side of a long-lived AEAD session that follows the key schedule of a
+/// `SealingSession`
+///
+/// Messages may arrive out of order as long as they are within `REPLAY_WINDOW` of the highest
+/// received sequence number and belong to the current or the previous epoch.
+pub struct OpeningSession {
+	cipher: Box<dyn AeadCipher>,
+	current: Epoch,
+	previous: Option<Epoch>
+}
+impl OpeningSession {
+	/// Creates a new session with `cipher` and the initial `key`
+	pub fn new(cipher: Ciphers, key: &[u8]) -> Result<Self, LibsodiumError> {
+		Ok(Self{ cipher: session_cipher(cipher)?, current: Epoch::new(key)?, previous: None })
+	}
+	
+	/// The current key epoch
+	pub fn epoch(&self) -> u32 {
+		self.current.number
+	}
+	
+	/// Opens the first `len` bytes in `buf` in place; returns the plaintext length
+	///
+	/// Returns `LibsodiumError::InvalidData` if the message is forged, corrupted, replayed or too
+	/// old. The session is not modified in this case.
+	pub fn open(&mut self, buf: &mut[u8], len: usize, ad: &[u8]) -> Result<usize, Box<dyn Error>> {
+		// Check parameters and parse the header
+		check!(buf.len() >= len, LibsodiumError::ApiMisuse("Buffer is too small"));
+		check!(len >= HEADER_LEN, LibsodiumError::InvalidData);
+		let number = u32::from_le_bytes(buf[..4].try_into().unwrap());
+		let seq = u64::from_le_bytes(buf[4..HEADER_LEN].try_into().unwrap());
+		
+		// Select the epoch or derive the next one
+		let mut next = None;
+		let epoch = match self.previous.as_ref() {
+			_ if number == self.current.number => &self.current,
+			Some(previous) if number == previous.number => previous,
+			_ => {
+				check!(number > self.current.number, LibsodiumError::InvalidData);
+				check!(number - self.current.number <= MAX_EPOCH_SKIP, LibsodiumError::InvalidData);
+				
+				let mut epoch = self.current.next()?;
+				while epoch.number < number {
+					epoch = epoch.next()?;
+				}
+				next.get_or_insert(epoch)
+			}
+		};
+		epoch.check(seq)?;
+		
+		// Open the message and move the plaintext to the beginning of the buffer
+		let nonce = nonce(buf, self.cipher.info().nonce_len);
+		let plaintext_len = self.cipher.open(
+			&mut buf[HEADER_LEN..], len - HEADER_LEN, ad, &epoch.key, &nonce
+		)?;
+		buf.copy_within(HEADER_LEN..HEADER_LEN + plaintext_len, 0);
+		
+		// Update the state
+		match next {
+			Some(next) => {
+				self.previous = Some(mem::replace(&mut self.current, next));
+				self.current.mark(seq);
+			},
+			None if number == self.current.number => self.current.mark(seq),
+			None => self.previous.as_mut().unwrap().mark(seq)
+		}
+		Ok(plaintext_len)
+	}
+}
@@ -0,0 +1,168 @@
+use crate::{
+	LibsodiumError, SystemRng,
+	sodium_bindings::{
+		sodium_init, sodium_memzero, crypto_shorthash_siphash24, crypto_shorthash_siphashx24,
+		crypto_shorthash_siphash24_BYTES, crypto_shorthash_siphashx24_BYTES,
+		crypto_shorthash_siphash24_KEYBYTES
+	}
+};
+use std::{
+	cmp, error::Error,
+	hash::{ Hasher, BuildHasher }
+};
+use crypto_api::rng::SecureRng;
+
+
+/// The key length of SipHash-2-4
+pub const KEY_LEN: usize = crypto_shorthash_siphash24_KEYBYTES as usize;
+
+
+/// Computes the 64 bit [SipHash-2-4](https://131002.net/siphash/) of `data` with the `KEY_LEN` byte
+/// `key`
+///
+/// _Note: SipHash is a keyed short-input PRF (e.g. for hash tables) and no replacement for a MAC
+/// or a collision-resistant hash_
+pub fn siphash24(key: &[u8], data: &[u8]) -> Result<u64, Box<dyn Error>> {
+	// Check parameters
+	check!(key.len() == KEY_LEN, LibsodiumError::ApiMisuse("Invalid key length"));
+	
+	// Compute the hash
+	let mut hash = [0u8; crypto_shorthash_siphash24_BYTES as usize];
+	assert_eq!(sodium!(
+		hash.as_mut_ptr(), data.as_ptr(), data.len(), key.as_ptr() => crypto_shorthash_siphash24
+	), 0);
+	Ok(u64::from_le_bytes(hash))
+}
+/// Computes the 128 bit SipHash-2-4 of `data` with the `KEY_LEN` byte `key`
+///
+/// _Note: SipHash is a keyed short-input PRF (e.g. for hash tables) and no replacement for a MAC
+/// or a collision-resistant hash_
+pub fn siphashx24(key: &[u8], data: &[u8]) -> Result<u128, Box<dyn Error>> {
+	// Check parameters
+	check!(key.len() == KEY_LEN, LibsodiumError::ApiMisuse("Invalid key length"));
+	
+	// Compute the hash
+	let mut hash = [0u8; crypto_shorthash_siphashx24_BYTES as usize];
+	assert_eq!(sodium!(
+		hash.as_mut_ptr(), data.as_ptr(), data.len(), key.as_ptr() => crypto_shorthash_siphashx24
+	), 0);
+	Ok(u128::from_le_bytes(hash))
+}
+
+
+/// The amount of bytes a `SipHasher24` buffers without allocating
+const INLINE_LEN: usize = 64;
+
+
+/// Overwrites `buf` with zeroes
+fn wipe(buf: &mut[u8]) {
+	sodium!(buf.as_mut_ptr(), buf.len() => sodium_memzero);
+}
+
+
+/// A `Hasher` that computes the 64 bit SipHash-2-4 over all written bytes
+///
+/// _Note: libsodium only provides a one-shot API, so the written bytes are buffered until `finish`
+/// is called; messages of up to 64 bytes are buffered inline, longer messages are moved to the
+/// heap. All buffers are zeroed when they are released_
+pub struct SipHasher24 {
+	key: [u8; KEY_LEN],
+	inline: [u8; INLINE_LEN],
+	inline_len: usize,
+	spilled: Vec<u8>
+}
+impl SipHasher24 {
+	/// Creates a new hasher with the `KEY_LEN` byte `key`
+	fn new(key: [u8; KEY_LEN]) -> Self {
+		Self{ key, inline: [0; INLINE_LEN], inline_len: 0, spilled: Vec::new() }
+	}
+	
+	/// The buffered bytes
+	fn data(&self) -> &[u8] {
+		match self.spilled.is_empty() {
+			true => &self.inline[..self.inline_len],
+			false => &self.spilled
+		}
+	}
+}
+impl Hasher for SipHasher24 {
+	fn finish(&self) -> u64 {
+		siphash24(&self.key, self.data()).expect("The key has a valid length")
+	}
+	fn write(&mut self, bytes: &[u8]) {
+		// Append to the inline buffer if possible
+		let inline_len = self.inline_len + bytes.len();
+		if self.spilled.is_empty() && inline_len <= INLINE_LEN {
+			self.inline[self.inline_len..inline_len].copy_from_slice(bytes);
+			self.inline_len = inline_len;
+			return;
+		}
+		
+		// Grow the heap buffer manually so that no unwiped copies are left behind
+		let len = self.data().len() + bytes.len();
+		if self.spilled.capacity() < len {
+			let mut spilled = Vec::with_capacity(cmp::max(len, 2 * INLINE_LEN));
+			spilled.extend_from_slice(self.data());
+			wipe(&mut self.inline[..self.inline_len]);
+			wipe(&mut self.spilled);
+			
+			self.inline_len = 0;
+			self.spilled = spilled;
+		}
+		self.spilled.extend_from_slice(bytes);
+	}
+}
+impl Drop for SipHasher24 {
+	fn drop(&mut self) {
+		wipe(&mut self.key);
+		wipe(&mut self.inline);
+		wipe(&mut self.spilled);
+	}
+}
+
+
+/// A `BuildHasher` that creates keyed `SipHasher24`s, e.g. for DoS-resistant `HashMap`s
+///
+/// All hashers built by the same instance (or its clones) use the same key; use
+/// `SodiumSipHasher::with_key` to get the same hashes across processes
+///
+/// _Note: building a hasher does not allocate, but since libsodium only provides a one-shot API,
+/// each hasher buffers the written bytes and allocates once more than 64 bytes are written (e.g.
+/// for long `String` keys)_
+#[derive(Clone)]
+pub struct SodiumSipHasher {
+	key: [u8; KEY_LEN]
+}
+impl SodiumSipHasher {
+	/// Creates a new builder with a random key from `SystemRng`
+	pub fn new() -> Result<Self, LibsodiumError> {
+		let mut key = [0; KEY_LEN];
+		SystemRng.random(&mut key).map_err(|_| LibsodiumError::ResourceError)?;
+		Ok(Self{ key })
+	}
+	/// Creates a new builder with the `KEY_LEN` byte `key`
+	pub fn with_key(key: &[u8]) -> Result<Self, LibsodiumError> {
+		check!(key.len() == KEY_LEN, LibsodiumError::ApiMisuse("Invalid key length"));
+		
+		let mut this = Self{ key: [0; KEY_LEN] };
+		this.key.copy_from_slice(key);
+		Ok(this)
+	}
+}
+impl Default for SodiumSipHasher {
+	/// Creates a new builder with a random key from `SystemRng`
+	fn default() -> Self {
+		Self::new().expect("Failed to generate a random key")
+	}
+}
+impl BuildHasher for SodiumSipHasher {
+	type Hasher = SipHasher24;
+	fn build_hasher(&self) -> Self::Hasher {
+		SipHasher24::new(self.key)
+	}
+}
+impl Drop for SodiumSipHasher {
+	fn drop(&mut self) {
+		sodium!(self.key.as_mut_ptr(), self.key.len() => sodium_memzero);
+	}
+}
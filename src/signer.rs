@@ -3,12 +3,20 @@ use crate::{
 	sodium_bindings::{
 		sodium_init, crypto_sign_ed25519_keypair, crypto_sign_ed25519_sk_to_pk,
 		crypto_sign_ed25519_detached, crypto_sign_ed25519_verify_detached,
-		crypto_sign_ed25519_BYTES,
+		crypto_sign_ed25519_seed_keypair, crypto_sign_ed25519_sk_to_seed, sodium_memzero,
+		crypto_sign_ed25519ph_state, crypto_sign_ed25519ph_init, crypto_sign_ed25519ph_update,
+		crypto_sign_ed25519ph_final_create, crypto_sign_ed25519ph_final_verify,
+		crypto_sign_ed25519, crypto_sign_ed25519_open,
+		crypto_sign_ed25519_pk_to_curve25519, crypto_sign_ed25519_sk_to_curve25519,
+		crypto_core_ed25519_is_valid_point, sodium_compare,
+		crypto_scalarmult_curve25519, crypto_scalarmult_curve25519_BYTES,
+		crypto_sign_ed25519_BYTES, crypto_sign_ed25519_SEEDBYTES,
 		crypto_sign_ed25519_SECRETKEYBYTES, crypto_sign_ed25519_PUBLICKEYBYTES
 	}
 };
 use std::{
-	ptr, error::Error,
+	ptr, mem, cmp, thread, error::Error,
+	io::{ self, Write },
 	os::raw::{ c_uchar, c_int, c_ulonglong }
 };
 use crypto_api::{
@@ -17,6 +25,60 @@ use crypto_api::{
 };
 
 
+/// Adapts the multi-part Ed25519ph API to the signature of `crypto_sign_ed25519_detached`
+unsafe extern "C" fn crypto_sign_ed25519ph_detached(sig: *mut c_uchar, siglen_p: *mut c_ulonglong,
+	m: *const c_uchar, mlen: c_ulonglong, sk: *const c_uchar) -> c_int
+{
+	let mut state: crypto_sign_ed25519ph_state = mem::zeroed();
+	crypto_sign_ed25519ph_init(&mut state);
+	crypto_sign_ed25519ph_update(&mut state, m, mlen);
+	crypto_sign_ed25519ph_final_create(&mut state, sig, siglen_p, sk)
+}
+/// Adapts the multi-part Ed25519ph API to the signature of `crypto_sign_ed25519_verify_detached`
+unsafe extern "C" fn crypto_sign_ed25519ph_verify_detached(sig: *const c_uchar, m: *const c_uchar,
+	mlen: c_ulonglong, pk: *const c_uchar) -> c_int
+{
+	let mut state: crypto_sign_ed25519ph_state = mem::zeroed();
+	crypto_sign_ed25519ph_init(&mut state);
+	crypto_sign_ed25519ph_update(&mut state, m, mlen);
+	crypto_sign_ed25519ph_final_verify(&mut state, sig, pk)
+}
+
+
+/// The order `L` of the Ed25519 base point as little-endian scalar
+const ED25519_ORDER: [u8; 32] = [
+	0xed, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9, 0xde, 0x14,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10
+];
+/// `crypto_sign_ed25519_verify_detached` with the additional checks of `Signers::Ed25519Strict`
+unsafe extern "C" fn crypto_sign_ed25519_verify_detached_strict(sig: *const c_uchar,
+	m: *const c_uchar, mlen: c_ulonglong, pk: *const c_uchar) -> c_int
+{
+	// Reject a non-reduced `S` and non-canonical, small-order or mixed-order `R` and `A`
+	let s = sig.add(crypto_sign_ed25519_BYTES as usize / 2);
+	if sodium_compare(s, ED25519_ORDER.as_ptr(), ED25519_ORDER.len()) != -1 { return -1 }
+	if crypto_core_ed25519_is_valid_point(sig) != 1 { return -1 }
+	if crypto_core_ed25519_is_valid_point(pk) != 1 { return -1 }
+	
+	crypto_sign_ed25519_verify_detached(sig, m, mlen, pk)
+}
+/// `crypto_sign_ed25519_open` with the additional checks of `Signers::Ed25519Strict`
+unsafe extern "C" fn crypto_sign_ed25519_open_strict(m: *mut c_uchar, mlen_p: *mut c_ulonglong,
+	sm: *const c_uchar, smlen: c_ulonglong, pk: *const c_uchar) -> c_int
+{
+	// Verify the signature
+	let sig_len = crypto_sign_ed25519_BYTES as c_ulonglong;
+	if smlen < sig_len { return -1 }
+	let (data, data_len) = (sm.add(sig_len as usize), smlen - sig_len);
+	if crypto_sign_ed25519_verify_detached_strict(sm, data, data_len, pk) != 0 { return -1 }
+	
+	// Copy the message if requested
+	if !m.is_null() { ptr::copy(data, m, data_len as usize) }
+	if !mlen_p.is_null() { *mlen_p = data_len }
+	0
+}
+
+
 /// An Ed25519 implementation
 struct Ed25519 {
 	pub signer: Signers,
@@ -32,12 +94,57 @@ struct Ed25519 {
 	) -> c_int,
 	
 	pub new_sec_key: unsafe extern "C" fn(pk: *mut c_uchar, sk: *mut c_uchar) -> c_int,
-	pub get_pub_key: unsafe extern "C" fn(pk: *mut c_uchar, sk: *const c_uchar) -> c_int
+	pub get_pub_key: unsafe extern "C" fn(pk: *mut c_uchar, sk: *const c_uchar) -> c_int,
+	
+	pub seed_keypair: unsafe extern "C" fn(
+		pk: *mut c_uchar, sk: *mut c_uchar, seed: *const c_uchar
+	) -> c_int,
+	pub sk_to_seed: unsafe extern "C" fn(seed: *mut c_uchar, sk: *const c_uchar) -> c_int,
+	
+	pub sign_combined: Option<unsafe extern "C" fn(
+		sm: *mut c_uchar, smlen_p: *mut c_ulonglong,
+		m: *const c_uchar, mlen: c_ulonglong,
+		sk: *const c_uchar
+	) -> c_int>,
+	pub open: Option<unsafe extern "C" fn(
+		m: *mut c_uchar, mlen_p: *mut c_ulonglong,
+		sm: *const c_uchar, smlen: c_ulonglong,
+		pk: *const c_uchar
+	) -> c_int>
+}
+impl Ed25519 {
+	/// Calls `f` with the 64 byte secret key for `sec_key` which is either a 64 byte secret key or
+	/// a 32 byte seed
+	fn with_sec_key<T>(&self, sec_key: &[u8], f: impl FnOnce(&[u8]) -> T)
+		-> Result<T, LibsodiumError>
+	{
+		let info = self.info();
+		match sec_key.len() {
+			len if len == info.sec_key_len => Ok(f(sec_key)),
+			len if len == SEED_LEN => {
+				// Expand the seed into a temporary secret key
+				let mut pub_key = [0u8; crypto_sign_ed25519_PUBLICKEYBYTES as usize];
+				let mut expanded = [0u8; crypto_sign_ed25519_SECRETKEYBYTES as usize];
+				assert_eq!(sodium!(
+					pub_key.as_mut_ptr(), expanded.as_mut_ptr(), sec_key.as_ptr() => self.seed_keypair
+				), 0);
+				
+				let result = f(&expanded);
+				sodium!(expanded.as_mut_ptr(), expanded.len() => sodium_memzero);
+				Ok(result)
+			},
+			_ => Err(LibsodiumError::ApiMisuse("Invalid key length"))
+		}
+	}
 }
 impl Signer for Ed25519 {
 	fn info(&self) -> SignerInfo {
 		SignerInfo {
-			name: "Ed25519",
+			name: match self.signer {
+				Signers::Ed25519 => "Ed25519",
+				Signers::Ed25519Strict => "Ed25519Strict",
+				Signers::Ed25519ph => "Ed25519ph"
+			},
 			sig_len: crypto_sign_ed25519_BYTES as usize,
 			sec_key_len: crypto_sign_ed25519_SECRETKEYBYTES as usize,
 			pub_key_len: crypto_sign_ed25519_PUBLICKEYBYTES as usize
@@ -50,13 +157,12 @@ impl Signer for Ed25519 {
 		// Check parameters
 		let info = self.info();
 		check!(buf.len() >= info.sig_len, LibsodiumError::ApiMisuse("Buffer is too small"));
-		check!(sec_key.len() == info.sec_key_len, LibsodiumError::ApiMisuse("Invalid key length"));
 		
 		// Sign the data
-		assert_eq!(sodium!(
+		self.with_sec_key(sec_key, |sec_key| assert_eq!(sodium!(
 			buf.as_mut_ptr(), ptr::null_mut(), data.as_ptr(), data.len(), sec_key.as_ptr()
 				=> self.sign
-		), 0);
+		), 0))?;
 		Ok(info.sig_len)
 	}
 	fn verify(&self, data: &[u8], sig: &[u8], pub_key: &[u8])
@@ -92,42 +198,472 @@ impl PubKeyGen for Ed25519 {
 		// Check the buffer length
 		let info = self.info();
 		check!(buf.len() >= info.pub_key_len, LibsodiumError::ApiMisuse("Buffer is too small"));
-		check!(sec_key.len() == info.sec_key_len, LibsodiumError::ApiMisuse("Invalid key length"));
 		
 		// Compute the public key
-		let mut _pub_key = [0u8; crypto_sign_ed25519_PUBLICKEYBYTES as usize];
-		assert_eq!(sodium!(buf.as_mut_ptr(), sec_key.as_ptr() => self.get_pub_key), 0);
+		self.with_sec_key(sec_key, |sec_key| {
+			assert_eq!(sodium!(buf.as_mut_ptr(), sec_key.as_ptr() => self.get_pub_key), 0)
+		})?;
 		Ok(info.pub_key_len)
 	}
 }
+impl SeedSigner for Ed25519 {
+	fn sec_key_from_seed(&self, buf: &mut[u8], seed: &[u8]) -> Result<usize, Box<dyn Error>> {
+		// Check parameters
+		let info = self.info();
+		check!(buf.len() >= info.sec_key_len, LibsodiumError::ApiMisuse("Buffer is too small"));
+		check!(seed.len() == SEED_LEN, LibsodiumError::ApiMisuse("Invalid seed length"));
+		
+		// Derive the key
+		let mut _pub_key = [0u8; crypto_sign_ed25519_PUBLICKEYBYTES as usize];
+		assert_eq!(sodium!(
+			_pub_key.as_mut_ptr(), buf.as_mut_ptr(), seed.as_ptr() => self.seed_keypair
+		), 0);
+		Ok(info.sec_key_len)
+	}
+	
+	fn sec_key_to_seed(&self, buf: &mut[u8], sec_key: &[u8]) -> Result<usize, Box<dyn Error>> {
+		// Check parameters
+		let info = self.info();
+		check!(buf.len() >= SEED_LEN, LibsodiumError::ApiMisuse("Buffer is too small"));
+		check!(sec_key.len() == info.sec_key_len, LibsodiumError::ApiMisuse("Invalid key length"));
+		
+		// Extract the seed
+		assert_eq!(sodium!(buf.as_mut_ptr(), sec_key.as_ptr() => self.sk_to_seed), 0);
+		Ok(SEED_LEN)
+	}
+}
+
+
+impl CombinedSigner for Ed25519 {
+	fn sign_combined(&self, buf: &mut[u8], data: &[u8], sec_key: &[u8])
+		-> Result<usize, Box<dyn Error>>
+	{
+		// Check parameters
+		let info = self.info();
+		check!(
+			buf.len() >= info.sig_len + data.len(),
+			LibsodiumError::ApiMisuse("Buffer is too small")
+		);
+		
+		// Sign the data
+		let sign_combined = self.sign_combined.ok_or(LibsodiumError::Unsupported)?;
+		self.with_sec_key(sec_key, |sec_key| assert_eq!(sodium!(
+			buf.as_mut_ptr(), ptr::null_mut(), data.as_ptr(), data.len(), sec_key.as_ptr()
+				=> sign_combined
+		), 0))?;
+		Ok(info.sig_len + data.len())
+	}
+	
+	fn open<'a>(&self, signed: &'a[u8], pub_key: &[u8]) -> Result<&'a[u8], Box<dyn Error>> {
+		// Check parameters
+		let info = self.info();
+		check!(pub_key.len() == info.pub_key_len, LibsodiumError::ApiMisuse("Invalid key length"));
+		check!(signed.len() >= info.sig_len, LibsodiumError::Truncated);
+		
+		// Verify the signature (libsodium does not copy the message if `m` is `NULL`)
+		let open = self.open.ok_or(LibsodiumError::Unsupported)?;
+		match sodium!(
+			ptr::null_mut::<c_uchar>(), ptr::null_mut::<c_ulonglong>(), signed.as_ptr(),
+			signed.len(), pub_key.as_ptr() => open
+		) {
+			0 => Ok(&signed[info.sig_len..]),
+			_ => Err(LibsodiumError::InvalidData)?
+		}
+	}
+}
+
+
+impl ContextSigner for Ed25519 {
+	fn sign_with_context(&self, buf: &mut[u8], context: &SigningContext, data: &[u8],
+		sec_key: &[u8]) -> Result<usize, Box<dyn Error>>
+	{
+		let payload = context.payload(data)?;
+		self.sign(buf, &payload, sec_key)
+	}
+	
+	fn verify_with_context(&self, context: &SigningContext, data: &[u8], sig: &[u8],
+		pub_key: &[u8]) -> Result<(), Box<dyn Error>>
+	{
+		let payload = context.payload(data)?;
+		self.verify(&payload, sig, pub_key)
+	}
+}
+
+
+impl BatchVerifier for Ed25519 {
+	fn verify_batch(&self, batch: &[(&[u8], &[u8], &[u8])], threads: usize)
+		-> Result<(), Vec<(usize, LibsodiumError)>>
+	{
+		// Distribute the items evenly over the workers
+		let per_worker = cmp::max(batch.len().div_ceil(cmp::max(threads, 1)), 1);
+		let mut failed: Vec<_> = thread::scope(|scope| {
+			let workers: Vec<_> = batch.chunks(per_worker).enumerate()
+				.map(|(i, items)| scope.spawn(move || {
+					// Verify the items and collect the failed ones with their absolute index
+					items.iter().enumerate().filter_map(|(j, (data, sig, pub_key))| {
+						let err = match self.verify(data, sig, pub_key).err()?.downcast_ref() {
+							Some(e) => *e,
+							None => LibsodiumError::InvalidData
+						};
+						Some((i * per_worker + j, err))
+					}).collect::<Vec<_>>()
+				}))
+				.collect();
+			workers.into_iter().flat_map(|w| w.join().expect("A worker thread panicked")).collect()
+		});
+		
+		failed.sort_by_key(|(i, _)| *i);
+		match failed.is_empty() {
+			true => Ok(()),
+			false => Err(failed)
+		}
+	}
+}
+
+
+/// The length of a seed from which a secret key can be derived
+pub const SEED_LEN: usize = crypto_sign_ed25519_SEEDBYTES as usize;
+
+
+/// A signer whose secret keys can be derived deterministically from a `SEED_LEN` byte seed
+///
+/// _Note: `Signer::sign` and `PubKeyGen::get_pub_key` of such a signer accept either a full secret
+/// key or a seed as `sec_key`_
+pub trait SeedSigner: Signer {
+	/// Derives the secret key from `seed` into `buf`; returns the length of the secret key
+	fn sec_key_from_seed(&self, buf: &mut[u8], seed: &[u8]) -> Result<usize, Box<dyn Error>>;
+	/// Extracts the seed from `sec_key` into `buf`; returns the length of the seed
+	fn sec_key_to_seed(&self, buf: &mut[u8], sec_key: &[u8]) -> Result<usize, Box<dyn Error>>;
+}
+
+
+/// A multi-part [Ed25519ph](https://tools.ietf.org/html/rfc8032#section-5.1) state to sign or
+/// verify large messages incrementally
+///
+/// _Note: the signatures are compatible with `Signers::Ed25519ph` but not with `Signers::Ed25519`_
+pub struct Ed25519phState {
+	state: crypto_sign_ed25519ph_state
+}
+impl Ed25519phState {
+	/// Creates a new state
+	pub fn new() -> Self {
+		let mut state: crypto_sign_ed25519ph_state = unsafe{ mem::zeroed() };
+		assert_eq!(sodium!(
+			&mut state as *mut crypto_sign_ed25519ph_state => crypto_sign_ed25519ph_init
+		), 0);
+		Self{ state }
+	}
+	
+	/// Adds `data` to the message
+	pub fn update(&mut self, data: &[u8]) {
+		assert_eq!(sodium!(
+			&mut self.state as *mut crypto_sign_ed25519ph_state, data.as_ptr(), data.len()
+				=> crypto_sign_ed25519ph_update
+		), 0);
+	}
+	
+	/// Signs the message with `sec_key` (which is either a 64 byte secret key or a 32 byte seed)
+	/// and writes the signature into `buf`; returns the signature length
+	pub fn sign(mut self, buf: &mut[u8], sec_key: &[u8]) -> Result<usize, Box<dyn Error>> {
+		// Check parameters
+		let info = Signers::Ed25519ph.ed25519().info();
+		check!(buf.len() >= info.sig_len, LibsodiumError::ApiMisuse("Buffer is too small"));
+		
+		// Sign the message
+		Signers::Ed25519ph.ed25519().with_sec_key(sec_key, |sec_key| assert_eq!(sodium!(
+			&mut self.state as *mut crypto_sign_ed25519ph_state, buf.as_mut_ptr(),
+			ptr::null_mut::<c_ulonglong>(), sec_key.as_ptr() => crypto_sign_ed25519ph_final_create
+		), 0))?;
+		Ok(info.sig_len)
+	}
+	
+	/// Verifies the signature `sig` over the message with `pub_key`
+	pub fn verify(mut self, sig: &[u8], pub_key: &[u8]) -> Result<(), Box<dyn Error>> {
+		// Check parameters
+		let info = Signers::Ed25519ph.ed25519().info();
+		check!(sig.len() == info.sig_len, LibsodiumError::ApiMisuse("Invalid signature length"));
+		check!(pub_key.len() == info.pub_key_len, LibsodiumError::ApiMisuse("Invalid key length"));
+		
+		// Verify the signature
+		match sodium!(
+			&mut self.state as *mut crypto_sign_ed25519ph_state, sig.as_ptr(), pub_key.as_ptr()
+				=> crypto_sign_ed25519ph_final_verify
+		) {
+			0 => Ok(()),
+			_ => Err(LibsodiumError::InvalidData)?
+		}
+	}
+}
+impl Default for Ed25519phState {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+impl Write for Ed25519phState {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.update(buf);
+		Ok(buf.len())
+	}
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+impl Drop for Ed25519phState {
+	fn drop(&mut self) {
+		sodium!(
+			&mut self.state as *mut crypto_sign_ed25519ph_state,
+			mem::size_of::<crypto_sign_ed25519ph_state>() => sodium_memzero
+		);
+	}
+}
+
+
+/// The length of an X25519 public or secret key
+pub const X25519_KEY_LEN: usize = crypto_scalarmult_curve25519_BYTES as usize;
+
+
+/// Converts the Ed25519 public key `pub_key` into an X25519 public key and writes it into `buf`;
+/// returns the key length
+///
+/// Returns `LibsodiumError::InvalidData` if `pub_key` is not a valid Ed25519 public key
+pub fn ed25519_pub_key_to_x25519(buf: &mut[u8], pub_key: &[u8]) -> Result<usize, Box<dyn Error>> {
+	// Check parameters
+	let info = Signers::Ed25519.ed25519().info();
+	check!(buf.len() >= X25519_KEY_LEN, LibsodiumError::ApiMisuse("Buffer is too small"));
+	check!(pub_key.len() == info.pub_key_len, LibsodiumError::ApiMisuse("Invalid key length"));
+	
+	// Convert the key
+	match sodium!(buf.as_mut_ptr(), pub_key.as_ptr() => crypto_sign_ed25519_pk_to_curve25519) {
+		0 => Ok(X25519_KEY_LEN),
+		_ => Err(LibsodiumError::InvalidData)?
+	}
+}
+/// Converts the Ed25519 secret key `sec_key` (which is either a 64 byte secret key or a 32 byte
+/// seed) into an X25519 secret key and writes it into `buf`; returns the key length
+///
+/// _Note: the resulting key pair is only as secure as the Ed25519 key pair; it is advisable to not
+/// use the same identity for signatures and key exchange unless the protocol requires it_
+pub fn ed25519_sec_key_to_x25519(buf: &mut[u8], sec_key: &[u8]) -> Result<usize, Box<dyn Error>> {
+	// Check parameters
+	check!(buf.len() >= X25519_KEY_LEN, LibsodiumError::ApiMisuse("Buffer is too small"));
+	
+	// Convert the key
+	Signers::Ed25519.ed25519().with_sec_key(sec_key, |sec_key| assert_eq!(sodium!(
+		buf.as_mut_ptr(), sec_key.as_ptr() => crypto_sign_ed25519_sk_to_curve25519
+	), 0))?;
+	Ok(X25519_KEY_LEN)
+}
+/// Computes the X25519 shared secret of the X25519 secret key `sec_key` and the X25519 public key
+/// `pub_key` and writes it into `buf`; returns the shared secret length
+///
+/// Returns `LibsodiumError::InvalidData` if `pub_key` is a low-order point (i.e. the shared secret
+/// would be all-zero)
+///
+/// _Note: the shared secret is a raw group element and should be passed through a KDF or hash
+/// before it is used as a key_
+pub fn x25519(buf: &mut[u8], sec_key: &[u8], pub_key: &[u8]) -> Result<usize, Box<dyn Error>> {
+	// Check parameters
+	check!(buf.len() >= X25519_KEY_LEN, LibsodiumError::ApiMisuse("Buffer is too small"));
+	check!(sec_key.len() == X25519_KEY_LEN, LibsodiumError::ApiMisuse("Invalid key length"));
+	check!(pub_key.len() == X25519_KEY_LEN, LibsodiumError::ApiMisuse("Invalid key length"));
+	
+	// Compute the shared secret
+	let result = sodium!(
+		buf.as_mut_ptr(), sec_key.as_ptr(), pub_key.as_ptr() => crypto_scalarmult_curve25519
+	);
+	match result {
+		0 => Ok(X25519_KEY_LEN),
+		_ => Err(LibsodiumError::InvalidData)?
+	}
+}
+
+
+/// A signer that can verify many signatures at once
+pub trait BatchVerifier: Signer {
+	/// Verifies all `(data, sig, pub_key)` items in `batch` using `threads` worker threads
+	///
+	/// Returns the indices of all invalid items (in ascending order) together with the error that
+	/// `Signer::verify` returns for the item
+	fn verify_batch(&self, batch: &[(&[u8], &[u8], &[u8])], threads: usize)
+		-> Result<(), Vec<(usize, LibsodiumError)>>;
+}
+
+
+/// A signer that supports NaCl-style combined `signature || message` blobs
+pub trait CombinedSigner: Signer {
+	/// Signs `data` with `sec_key` and writes `signature || data` into `buf`; returns the length of
+	/// the signed blob
+	fn sign_combined(&self, buf: &mut[u8], data: &[u8], sec_key: &[u8])
+		-> Result<usize, Box<dyn Error>>;
+	/// Verifies the signed blob `signed` with `pub_key`; returns the verified message
+	///
+	/// Returns `LibsodiumError::Truncated` if `signed` is too short to contain a signature and
+	/// `LibsodiumError::InvalidData` if the signature is invalid
+	fn open<'a>(&self, signed: &'a[u8], pub_key: &[u8]) -> Result<&'a[u8], Box<dyn Error>>;
+}
+
+
+/// The magic bytes that start every payload signed by a `ContextSigner`
+pub const CONTEXT_MAGIC: &[u8; 8] = b"SIGNCTX1";
+
+
+/// A caller-chosen context that a `ContextSigner` binds into a signature
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct SigningContext<'a> {
+	/// The context label (1 to 255 bytes)
+	pub label: &'a[u8],
+	/// An optional protocol version
+	pub version: Option<u32>
+}
+impl<'a> SigningContext<'a> {
+	/// Creates a new context with `label` and no protocol version
+	pub fn new(label: &'a[u8]) -> Self {
+		Self{ label, version: None }
+	}
+	/// Creates a new context with `label` and the protocol version `version`
+	pub fn with_version(label: &'a[u8], version: u32) -> Self {
+		Self{ label, version: Some(version) }
+	}
+	
+	/// Encodes the payload that is signed instead of `data`:
+	/// `CONTEXT_MAGIC || u8(label.len()) || label || u8(version.is_some()) || le32(version) || data`
+	///
+	/// _Note: an absent version is encoded as `0x00 || le32(0)` and is therefore distinct from
+	/// version `0`_
+	pub fn payload(&self, data: &[u8]) -> Result<Vec<u8>, LibsodiumError> {
+		// Check the label length
+		check!(
+			!self.label.is_empty() && self.label.len() <= u8::MAX as usize,
+			LibsodiumError::ApiMisuse("Invalid context length")
+		);
+		
+		// Encode the payload
+		let len = CONTEXT_MAGIC.len() + 1 + self.label.len() + 1 + 4 + data.len();
+		let mut payload = Vec::with_capacity(len);
+		payload.extend_from_slice(CONTEXT_MAGIC);
+		payload.push(self.label.len() as u8);
+		payload.extend_from_slice(self.label);
+		payload.push(self.version.is_some() as u8);
+		payload.extend_from_slice(&self.version.unwrap_or(0).to_le_bytes());
+		payload.extend_from_slice(data);
+		Ok(payload)
+	}
+}
+
+
+/// A signer that binds a `SigningContext` into its signatures so that a signature created under
+/// one context is never valid under another
+///
+/// _Note: the context-bound payload starts with `CONTEXT_MAGIC`; to keep the domains separate,
+/// do not sign plain messages that start with `CONTEXT_MAGIC` with the same key_
+pub trait ContextSigner: Signer {
+	/// Signs `data` under `context` with `sec_key` and writes the signature into `buf`; returns the
+	/// signature length
+	fn sign_with_context(&self, buf: &mut[u8], context: &SigningContext, data: &[u8],
+		sec_key: &[u8]) -> Result<usize, Box<dyn Error>>;
+	/// Verifies the signature `sig` over `data` under `context` with `pub_key`
+	///
+	/// Returns `LibsodiumError::InvalidData` if the signature is invalid or was created under a
+	/// different context
+	fn verify_with_context(&self, context: &SigningContext, data: &[u8], sig: &[u8],
+		pub_key: &[u8]) -> Result<(), Box<dyn Error>>;
+}
 
 
 /// Signer implementations
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Signers {
 	/// [Ed25519](https://tools.ietf.org/html/rfc8032)
-	Ed25519
+	Ed25519,
+	/// [Ed25519](https://tools.ietf.org/html/rfc8032) with strict, canonical-encoding verification
+	///
+	/// Signatures are created like `Ed25519` but a signature `R || S` over a message `M` is only
+	/// accepted if
+	///  1. `S` is a canonical scalar (`S < L`),
+	///  2. `R` and the public key `A` are canonical encodings (`y < p`) of points that are neither
+	///     of small order nor have a small-order component (i.e. they lie in the prime-order
+	///     subgroup), and
+	///  3. the cofactorless equation `[S]B = R + [k]A` with `k = SHA-512(R || A || M)` holds and `R`
+	///     is the byte-exact encoding of `[S]B - [k]A`.
+	///
+	/// This accepts only the signatures an honest signer can create and rejects all malleable or
+	/// ambiguous encodings; it is stricter than [ZIP-215](https://zips.z.cash/zip-0215), which
+	/// e.g. accepts non-canonical point encodings and verifies with the cofactored equation.
+	Ed25519Strict,
+	/// [Ed25519ph](https://tools.ietf.org/html/rfc8032#section-5.1) (pre-hashed with SHA-512)
+	///
+	/// _Note: use `Ed25519phState` to sign or verify large messages incrementally_
+	Ed25519ph
 }
 impl Signers {
 	/// Selects a signer implementation from name
 	///
 	/// Currently supported names are:
 	///  - `Ed25519` which maps to Ed25519
+	///  - `Ed25519Strict` which maps to Ed25519 with strict verification
+	///  - `Ed25519ph` which maps to Ed25519ph
 	pub fn from_name(name: &str) -> Result<Self, LibsodiumError> {
 		Ok(match name {
 			"Ed25519" => Signers::Ed25519,
+			"Ed25519Strict" => Signers::Ed25519Strict,
+			"Ed25519ph" => Signers::Ed25519ph,
 			_ => return Err(LibsodiumError::Unsupported)
 		})
 	}
 	
 	/// Creates a new `Signer`-instance with this implementation
 	pub fn signer(self) -> Box<dyn Signer> {
+		Box::new(self.ed25519())
+	}
+	/// Creates a new `SeedSigner`-instance with this implementation
+	pub fn seed_signer(self) -> Result<Box<dyn SeedSigner>, LibsodiumError> {
+		Ok(Box::new(self.ed25519()))
+	}
+	/// Creates a new `BatchVerifier`-instance with this implementation
+	pub fn batch_verifier(self) -> Result<Box<dyn BatchVerifier>, LibsodiumError> {
+		Ok(Box::new(self.ed25519()))
+	}
+	/// Creates a new `ContextSigner`-instance with this implementation
+	pub fn context_signer(self) -> Result<Box<dyn ContextSigner>, LibsodiumError> {
+		Ok(Box::new(self.ed25519()))
+	}
+	/// Creates a new `CombinedSigner`-instance with this implementation
+	///
+	/// Supported are `Ed25519` and `Ed25519Strict`
+	pub fn combined_signer(self) -> Result<Box<dyn CombinedSigner>, LibsodiumError> {
+		match self.ed25519() {
+			ed25519 if ed25519.sign_combined.is_some() => Ok(Box::new(ed25519)),
+			_ => Err(LibsodiumError::Unsupported)
+		}
+	}
+	
+	/// Creates the Ed25519 implementation
+	fn ed25519(self) -> Ed25519 {
 		match self {
-			Signers::Ed25519 => Box::new(Ed25519 {
+			Signers::Ed25519 => Ed25519 {
 				signer: self,
 				sign: crypto_sign_ed25519_detached, verify: crypto_sign_ed25519_verify_detached,
-				new_sec_key: crypto_sign_ed25519_keypair, get_pub_key: crypto_sign_ed25519_sk_to_pk
-			})
+				new_sec_key: crypto_sign_ed25519_keypair, get_pub_key: crypto_sign_ed25519_sk_to_pk,
+				seed_keypair: crypto_sign_ed25519_seed_keypair,
+				sk_to_seed: crypto_sign_ed25519_sk_to_seed,
+				sign_combined: Some(crypto_sign_ed25519), open: Some(crypto_sign_ed25519_open)
+			},
+			Signers::Ed25519Strict => Ed25519 {
+				signer: self,
+				sign: crypto_sign_ed25519_detached, verify: crypto_sign_ed25519_verify_detached_strict,
+				new_sec_key: crypto_sign_ed25519_keypair, get_pub_key: crypto_sign_ed25519_sk_to_pk,
+				seed_keypair: crypto_sign_ed25519_seed_keypair,
+				sk_to_seed: crypto_sign_ed25519_sk_to_seed,
+				sign_combined: Some(crypto_sign_ed25519), open: Some(crypto_sign_ed25519_open_strict)
+			},
+			Signers::Ed25519ph => Ed25519 {
+				signer: self,
+				sign: crypto_sign_ed25519ph_detached, verify: crypto_sign_ed25519ph_verify_detached,
+				new_sec_key: crypto_sign_ed25519_keypair, get_pub_key: crypto_sign_ed25519_sk_to_pk,
+				seed_keypair: crypto_sign_ed25519_seed_keypair,
+				sk_to_seed: crypto_sign_ed25519_sk_to_seed,
+				sign_combined: None, open: None
+			}
 		}
 	}
 }
\ No newline at end of file
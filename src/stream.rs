@@ -0,0 +1,201 @@
+use crate::{
+	LibsodiumError, Ciphers,
+	sodium_bindings::{ sodium_init, sodium_memzero }
+};
+use std::{ cmp, thread, ops::Range, error::Error };
+use crypto_api::cipher::AeadCipher;
+
+
+/// The amount of nonce bytes used by the chunk counter (`u32 BE`) and the last-chunk flag
+pub const NONCE_OVERHEAD: usize = 5;
+/// The maximum amount of chunks
+pub const MAX_CHUNKS: u64 = 1 << 32;
+
+
+/// The [STREAM](https://eprint.iacr.org/2015/189.pdf) online-AE construction on top of an AEAD
+/// cipher
+///
+/// A blob is split into chunks of `chunk_len` bytes which are sealed independently with the nonce
+/// `prefix || counter (u32 BE) || last-chunk flag`. This allows parallel encryption/decryption and
+/// random access to individual chunks while detecting reordered, dropped or truncated chunks.
+///
+/// _Note: the same key/prefix combination must never be used for two blobs_
+pub struct Stream {
+	cipher: Ciphers,
+	chunk_len: usize,
+	key_len: usize,
+	nonce_len: usize,
+	tag_len: usize
+}
+impl Stream {
+	/// Creates a new STREAM instance for `cipher` with `chunk_len` plaintext bytes per chunk
+	pub fn new(cipher: Ciphers, chunk_len: usize) -> Result<Self, LibsodiumError> {
+		check!(chunk_len > 0, LibsodiumError::ApiMisuse("Invalid chunk length"));
+		
+		let info = cipher.aead_cipher()?.info();
+		check!(info.nonce_len > NONCE_OVERHEAD, LibsodiumError::Unsupported);
+		Ok(Self{
+			cipher, chunk_len,
+			key_len: info.key_len, nonce_len: info.nonce_len, tag_len: info.aead_tag_len.unwrap()
+		})
+	}
+	
+	/// The length of the nonce prefix (which is the cipher's nonce length minus `NONCE_OVERHEAD`)
+	pub fn prefix_len(&self) -> usize {
+		self.nonce_len - NONCE_OVERHEAD
+	}
+	/// The length of a full encrypted chunk
+	pub fn encrypted_chunk_len(&self) -> usize {
+		self.chunk_len + self.tag_len
+	}
+	/// The length of the encrypted blob for `plaintext_len` bytes
+	pub fn encrypted_len(&self, plaintext_len: usize) -> usize {
+		plaintext_len + self.chunk_count(plaintext_len) * self.tag_len
+	}
+	
+	/// Seals the first `plaintext_len` bytes in `buf` in place using `threads` worker threads;
+	/// returns the length of the encrypted blob
+	pub fn seal(&self, buf: &mut[u8], plaintext_len: usize, ad: &[u8], key: &[u8], prefix: &[u8],
+		threads: usize) -> Result<usize, Box<dyn Error>>
+	{
+		// Check parameters
+		let chunk_count = self.chunk_count(plaintext_len);
+		let encrypted_len = self.encrypted_len(plaintext_len);
+		check!(buf.len() >= encrypted_len, LibsodiumError::ApiMisuse("Buffer is too small"));
+		self.check_params(chunk_count, key, prefix)?;
+		
+		// Move the plaintext chunks to their final positions (back to front to avoid overlaps)
+		for i in (1..chunk_count).rev() {
+			let start = i * self.chunk_len;
+			let end = cmp::min(start + self.chunk_len, plaintext_len);
+			buf.copy_within(start..end, i * self.encrypted_chunk_len());
+		}
+		
+		// Seal the chunks
+		let chunks = buf[..encrypted_len].chunks_mut(self.encrypted_chunk_len()).enumerate()
+			.collect();
+		self.process(chunks, threads, |aead, i, chunk| {
+			let nonce = self.nonce(prefix, i, i + 1 == chunk_count);
+			aead.seal(chunk, chunk.len() - self.tag_len, ad, key, &nonce)
+		})?;
+		Ok(encrypted_len)
+	}
+	
+	/// Opens the first `ciphertext_len` bytes in `buf` in place using `threads` worker threads;
+	/// returns the plaintext length
+	///
+	/// Returns `LibsodiumError::InvalidData` if any chunk is forged, reordered or if the blob has
+	/// been truncated; in this case the first `ciphertext_len` bytes in `buf` are zeroed
+	pub fn open(&self, buf: &mut[u8], ciphertext_len: usize, ad: &[u8], key: &[u8], prefix: &[u8],
+		threads: usize) -> Result<usize, Box<dyn Error>>
+	{
+		// Check parameters
+		check!(buf.len() >= ciphertext_len, LibsodiumError::ApiMisuse("Buffer is too small"));
+		let chunk_count = self.encrypted_chunk_count(ciphertext_len)?;
+		self.check_params(chunk_count, key, prefix)?;
+		
+		// Open the chunks
+		let chunks = buf[..ciphertext_len].chunks_mut(self.encrypted_chunk_len()).enumerate()
+			.collect();
+		let result = self.process(chunks, threads, |aead, i, chunk| {
+			let nonce = self.nonce(prefix, i, i + 1 == chunk_count);
+			aead.open(chunk, chunk.len(), ad, key, &nonce)
+		});
+		
+		// Wipe the buffer if any chunk is invalid so that no unauthenticated plaintext is released
+		if result.is_err() {
+			sodium!(buf.as_mut_ptr(), ciphertext_len => sodium_memzero);
+		}
+		result?;
+		
+		// Move the plaintext chunks to the beginning of the buffer
+		for i in 1..chunk_count {
+			let start = i * self.encrypted_chunk_len();
+			let end = cmp::min(start + self.chunk_len, ciphertext_len - self.tag_len);
+			buf.copy_within(start..end, i * self.chunk_len);
+		}
+		Ok(ciphertext_len - chunk_count * self.tag_len)
+	}
+	
+	/// The position of the encrypted chunk with the index `index` within an encrypted blob of
+	/// `ciphertext_len` bytes
+	pub fn chunk_range(&self, index: usize, ciphertext_len: usize)
+		-> Result<Range<usize>, LibsodiumError>
+	{
+		check!(
+			index < self.encrypted_chunk_count(ciphertext_len)?,
+			LibsodiumError::ApiMisuse("Invalid chunk index")
+		);
+		let start = index * self.encrypted_chunk_len();
+		Ok(start .. cmp::min(start + self.encrypted_chunk_len(), ciphertext_len))
+	}
+	/// Opens the encrypted chunk with the index `index` of an encrypted blob of `ciphertext_len`
+	/// bytes in place; returns the plaintext length
+	///
+	/// `buf` must start with the encrypted chunk (see `chunk_range`)
+	pub fn open_chunk(&self, buf: &mut[u8], index: usize, ciphertext_len: usize, ad: &[u8],
+		key: &[u8], prefix: &[u8]) -> Result<usize, Box<dyn Error>>
+	{
+		// Check parameters
+		let range = self.chunk_range(index, ciphertext_len)?;
+		check!(buf.len() >= range.len(), LibsodiumError::ApiMisuse("Buffer is too small"));
+		self.check_params(index + 1, key, prefix)?;
+		
+		// Open the chunk
+		let nonce = self.nonce(prefix, index, range.end == ciphertext_len);
+		self.cipher.aead_cipher()?.open(buf, range.len(), ad, key, &nonce)
+	}
+	
+	
+	/// The amount of chunks for `plaintext_len` bytes (the last chunk may be empty)
+	fn chunk_count(&self, plaintext_len: usize) -> usize {
+		cmp::max(plaintext_len.div_ceil(self.chunk_len), 1)
+	}
+	/// The amount of chunks in an encrypted blob of `ciphertext_len` bytes
+	fn encrypted_chunk_count(&self, ciphertext_len: usize) -> Result<usize, LibsodiumError> {
+		let chunk_count = cmp::max(ciphertext_len.div_ceil(self.encrypted_chunk_len()), 1);
+		check!(
+			ciphertext_len >= (chunk_count - 1) * self.encrypted_chunk_len() + self.tag_len,
+			LibsodiumError::InvalidData
+		);
+		Ok(chunk_count)
+	}
+	/// Validates the chunk count, the key and the prefix
+	fn check_params(&self, chunk_count: usize, key: &[u8], prefix: &[u8])
+		-> Result<(), LibsodiumError>
+	{
+		check!(chunk_count as u64 <= MAX_CHUNKS, LibsodiumError::ApiMisuse("Too many chunks"));
+		check!(key.len() == self.key_len, LibsodiumError::ApiMisuse("Invalid key length"));
+		check!(prefix.len() == self.prefix_len(), LibsodiumError::ApiMisuse("Invalid nonce length"));
+		Ok(())
+	}
+	/// Creates the nonce for the chunk with the index `index`
+	fn nonce(&self, prefix: &[u8], index: usize, last: bool) -> Vec<u8> {
+		let mut nonce = prefix.to_vec();
+		nonce.extend_from_slice(&(index as u32).to_be_bytes());
+		nonce.push(last as u8);
+		nonce
+	}
+	/// Processes `chunks` with `f` distributed over `threads` worker threads
+	fn process<F>(&self, mut chunks: Vec<(usize, &mut[u8])>, threads: usize, f: F)
+		-> Result<(), LibsodiumError>
+		where F: Fn(&dyn AeadCipher, usize, &mut[u8]) -> Result<usize, Box<dyn Error>> + Sync
+	{
+		// Distribute the chunks evenly over the workers
+		let per_worker = chunks.len().div_ceil(cmp::max(threads, 1));
+		let f = &f;
+		thread::scope(|scope| {
+			let workers: Vec<_> = chunks.chunks_mut(per_worker).map(|chunks| scope.spawn(move || {
+				let aead = self.cipher.aead_cipher()?;
+				for (i, chunk) in chunks.iter_mut() {
+					f(aead.as_ref(), *i, chunk).map_err(|e| match e.downcast_ref::<LibsodiumError>() {
+						Some(e) => *e,
+						None => LibsodiumError::InvalidData
+					})?;
+				}
+				Ok(())
+			})).collect();
+			workers.into_iter().try_for_each(|w| w.join().expect("A worker thread panicked"))
+		})
+	}
+}
@@ -1,5 +1,5 @@
 use crypto_api::cipher::CipherInfo;
-use crypto_api_libsodium::{ LibsodiumError, Ciphers };
+use crypto_api_libsodium::{ LibsodiumError, Ciphers, cipher::Aes256GcmState };
 
 
 /// A helper macro to compare a `Box<dyn Error + 'static>` to a `LibsodiumError`
@@ -10,12 +10,27 @@ macro_rules! compare_err {
 }
 
 
+/// Checks if the implementation `name` is usable on the current hardware (AES-256-GCM requires
+/// hardware support and is skipped otherwise)
+fn is_available(name: &str) -> bool {
+	match name {
+		"Aes256Gcm" => Ciphers::Aes256Gcm.is_available(),
+		_ => true
+	}
+}
+
+
 /// A trait to extend the info-structs with the ability to test them
 trait PropertyTest {
 	fn test(&self);
 }
 impl PropertyTest for CipherInfo {
 	fn test(&self) {
+		// Skip hardware-dependent implementations that are not available
+		if !is_available(self.name) {
+			return;
+		}
+		
 		// Create cipher
 		let cipher = Ciphers::from_name(self.name).unwrap().cipher();
 		
@@ -93,6 +108,11 @@ struct CipherTestVector {
 }
 impl CipherTestVector {
 	pub fn test(&self) {
+		// Skip hardware-dependent implementations that are not available
+		if !is_available(self.name) {
+			return;
+		}
+		
 		match self.ad.len() {
 			0 => self.test_cipher(),
 			_ => self.test_aead_cipher()
@@ -141,6 +161,30 @@ impl CipherTestVector {
 			self.key, self.nonce
 		).unwrap();
 		assert_eq!(&buf[..plaintext_len], self.plaintext);
+		
+		self.test_detached_aead_cipher();
+	}
+	fn test_detached_aead_cipher(&self) {
+		// Create cipher and split the expected ciphertext into ciphertext and tag
+		let cipher = Ciphers::from_name(self.name).unwrap().detached_aead_cipher().unwrap();
+		let tag_len = cipher.info().aead_tag_len.unwrap();
+		let (ciphertext, tag) = self.ciphertext.split_at(self.ciphertext.len() - tag_len);
+		
+		// Test encryption
+		let (mut buf, mut tag_buf) = (self.plaintext.to_vec(), vec![0; tag_len]);
+		let ciphertext_len = cipher.seal_detached(
+			&mut buf, self.plaintext.len(), &mut tag_buf, self.ad,
+			self.key, self.nonce
+		).unwrap();
+		assert_eq!(&buf[..ciphertext_len], ciphertext);
+		assert_eq!(tag_buf.as_slice(), tag);
+		
+		// Test decryption
+		let plaintext_len = cipher.open_detached(
+			&mut buf, ciphertext_len, &tag_buf, self.ad,
+			self.key, self.nonce
+		).unwrap();
+		assert_eq!(&buf[..plaintext_len], self.plaintext);
 	}
 }
 
@@ -156,6 +200,11 @@ struct AeadErrorTestVector {
 }
 impl AeadErrorTestVector {
 	pub fn test(&self) {
+		// Skip hardware-dependent implementations that are not available
+		if !is_available(self.name) {
+			return;
+		}
+		
 		// Create cipher and test if cipher is available and if the cipher is an AEAD cipher
 		let cipher = Ciphers::from_name(self.name).unwrap().aead_cipher().unwrap();
 		
@@ -165,6 +214,44 @@ impl AeadErrorTestVector {
 			self.key, self.nonce
 		).unwrap_err();
 		compare_err!(err, LibsodiumError::InvalidData);
+		
+		// Try to decrypt the data with a detached tag
+		let cipher = Ciphers::from_name(self.name).unwrap().detached_aead_cipher().unwrap();
+		let tag_len = cipher.info().aead_tag_len.unwrap();
+		let (ciphertext, tag) = self.ciphertext.split_at(self.ciphertext.len() - tag_len);
+		let err = cipher.open_detached(
+			&mut ciphertext.to_vec(), ciphertext.len(), tag, self.ad,
+			self.key, self.nonce
+		).unwrap_err();
+		compare_err!(err, LibsodiumError::InvalidData);
+	}
+}
+
+
+/// A test vector to test an authenticated non-AEAD cipher
+struct ErrorTestVector {
+	pub name: &'static str,
+	
+	pub key: &'static[u8],
+	pub nonce: &'static[u8],
+	pub ciphertext: &'static[u8]
+}
+impl ErrorTestVector {
+	pub fn test(&self) {
+		// Skip hardware-dependent implementations that are not available
+		if !is_available(self.name) {
+			return;
+		}
+		
+		// Create cipher and test if cipher is available
+		let cipher = Ciphers::from_name(self.name).unwrap().cipher();
+		
+		// Try to decrypt the data
+		let err = cipher.decrypt(
+			&mut self.ciphertext.to_vec(), self.ciphertext.len(),
+			self.key, self.nonce
+		).unwrap_err();
+		compare_err!(err, LibsodiumError::InvalidData);
 	}
 }
 
@@ -185,6 +272,34 @@ fn test() {
 		aead_tag_len: Some(16)
 	}.test();
 	
+	CipherInfo {
+		name: "XChaCha20Poly1305Ietf",
+		key_len: 32,
+		nonce_len: 24,
+		aead_tag_len: Some(16)
+	}.test();
+	
+	CipherInfo {
+		name: "ChaCha20Poly1305",
+		key_len: 32,
+		nonce_len: 8,
+		aead_tag_len: Some(16)
+	}.test();
+	
+	CipherInfo {
+		name: "XSalsa20Poly1305",
+		key_len: 32,
+		nonce_len: 24,
+		aead_tag_len: Some(16)
+	}.test();
+	
+	CipherInfo {
+		name: "XChaCha20Poly1305",
+		key_len: 32,
+		nonce_len: 24,
+		aead_tag_len: Some(16)
+	}.test();
+	
 	CipherInfo {
 		name: "ChaCha20Ietf",
 		key_len: 32,
@@ -192,6 +307,41 @@ fn test() {
 		aead_tag_len: None
 	}.test();
 	
+	CipherInfo {
+		name: "XChaCha20",
+		key_len: 32,
+		nonce_len: 24,
+		aead_tag_len: None
+	}.test();
+	
+	CipherInfo {
+		name: "XSalsa20",
+		key_len: 32,
+		nonce_len: 24,
+		aead_tag_len: None
+	}.test();
+	
+	CipherInfo {
+		name: "Salsa20",
+		key_len: 32,
+		nonce_len: 8,
+		aead_tag_len: None
+	}.test();
+	
+	CipherInfo {
+		name: "Salsa2012",
+		key_len: 32,
+		nonce_len: 8,
+		aead_tag_len: None
+	}.test();
+	
+	CipherInfo {
+		name: "Salsa208",
+		key_len: 32,
+		nonce_len: 8,
+		aead_tag_len: None
+	}.test();
+	
 	
 	CipherTestVector {
 		name: "Aes256Gcm",
@@ -257,6 +407,114 @@ fn test() {
 	
 	
 	
+	CipherTestVector {
+		name: "XChaCha20Poly1305Ietf",
+		
+		key: b"\x80\x81\x82\x83\x84\x85\x86\x87\x88\x89\x8a\x8b\x8c\x8d\x8e\x8f\x90\x91\x92\x93\x94\x95\x96\x97\x98\x99\x9a\x9b\x9c\x9d\x9e\x9f",
+		nonce: b"\x40\x41\x42\x43\x44\x45\x46\x47\x48\x49\x4a\x4b\x4c\x4d\x4e\x4f\x50\x51\x52\x53\x54\x55\x56\x57",
+		plaintext: b"".as_ref(),
+		ad: b"".as_ref(),
+		ciphertext: b"\x1d\xac\x8f\x73\x14\x6d\x1e\x9d\xa7\x96\xcb\x7f\x72\x21\xa5\xdf".as_ref()
+	}.test();
+	
+	CipherTestVector {
+		name: "XChaCha20Poly1305Ietf",
+		
+		key: b"\x80\x81\x82\x83\x84\x85\x86\x87\x88\x89\x8a\x8b\x8c\x8d\x8e\x8f\x90\x91\x92\x93\x94\x95\x96\x97\x98\x99\x9a\x9b\x9c\x9d\x9e\x9f",
+		nonce: b"\x40\x41\x42\x43\x44\x45\x46\x47\x48\x49\x4a\x4b\x4c\x4d\x4e\x4f\x50\x51\x52\x53\x54\x55\x56\x57",
+		plaintext: b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.".as_ref(),
+		ad: b"\x50\x51\x52\x53\xc0\xc1\xc2\xc3\xc4\xc5\xc6\xc7".as_ref(),
+		ciphertext: b"\xbd\x6d\x17\x9d\x3e\x83\xd4\x3b\x95\x76\x57\x94\x93\xc0\xe9\x39\x57\x2a\x17\x00\x25\x2b\xfa\xcc\xbe\xd2\x90\x2c\x21\x39\x6c\xbb\x73\x1c\x7f\x1b\x0b\x4a\xa6\x44\x0b\xf3\xa8\x2f\x4e\xda\x7e\x39\xae\x64\xc6\x70\x8c\x54\xc2\x16\xcb\x96\xb7\x2e\x12\x13\xb4\x52\x2f\x8c\x9b\xa4\x0d\xb5\xd9\x45\xb1\x1b\x69\xb9\x82\xc1\xbb\x9e\x3f\x3f\xac\x2b\xc3\x69\x48\x8f\x76\xb2\x38\x35\x65\xd3\xff\xf9\x21\xf9\x66\x4c\x97\x63\x7d\xa9\x76\x88\x12\xf6\x15\xc6\x8b\x13\xb5\x2e\xc0\x87\x59\x24\xc1\xc7\x98\x79\x47\xde\xaf\xd8\x78\x0a\xcf\x49".as_ref()
+	}.test();
+	
+	CipherTestVector {
+		name: "XChaCha20Poly1305Ietf",
+		
+		key: b"\x80\x81\x82\x83\x84\x85\x86\x87\x88\x89\x8a\x8b\x8c\x8d\x8e\x8f\x90\x91\x92\x93\x94\x95\x96\x97\x98\x99\x9a\x9b\x9c\x9d\x9e\x9f",
+		nonce: b"\x40\x41\x42\x43\x44\x45\x46\x47\x48\x49\x4a\x4b\x4c\x4d\x4e\x4f\x50\x51\x52\x53\x54\x55\x56\x57",
+		plaintext: b"123456789abcdef0".as_ref(),
+		ad: b"1".as_ref(),
+		ciphertext: b"\xc0\x3e\x40\xc0\x6e\xc6\xc3\x62\xc2\x73\x15\xb0\x92\xcb\xfb\x65\xb6\xe9\xad\x42\x99\xe1\x46\x03\x08\xb7\xd1\xb1\x4a\x32\x2d\x5a".as_ref()
+	}.test();
+	
+	
+	
+	CipherTestVector {
+		name: "ChaCha20Poly1305",
+		
+		key: b"\x42\x90\xbc\xb1\x54\x17\x35\x31\xf3\x14\xaf\x57\xf3\xbe\x3b\x50\x06\xda\x37\x1e\xce\x27\x2a\xfa\x1b\x5d\xbd\xd1\x10\x0a\x10\x07",
+		nonce: b"\xcd\x7c\xf6\x7b\xe3\x9c\x79\x4a",
+		plaintext: b"\x86\xd0\x99\x74\x84\x0b\xde\xd2\xa5\xca".as_ref(),
+		ad: b"\x87\xe2\x29\xd4\x50\x08\x45\xa0\x79\xc0".as_ref(),
+		ciphertext: b"\xe3\xe4\x46\xf7\xed\xe9\xa1\x9b\x62\xa4\x67\x7d\xab\xf4\xe3\xd2\x4b\x87\x6b\xb2\x84\x75\x38\x96\xe1\xd6".as_ref()
+	}.test();
+	
+	CipherTestVector {
+		name: "ChaCha20Poly1305",
+		
+		key: b"\x80\x81\x82\x83\x84\x85\x86\x87\x88\x89\x8a\x8b\x8c\x8d\x8e\x8f\x90\x91\x92\x93\x94\x95\x96\x97\x98\x99\x9a\x9b\x9c\x9d\x9e\x9f",
+		nonce: b"\x07\x00\x00\x00\x40\x41\x42\x43",
+		plaintext: b"".as_ref(),
+		ad: b"".as_ref(),
+		ciphertext: b"\x34\x02\xb5\x4e\x24\x08\x9a\x6e\x01\x5a\xfd\xb1\xb5\x5c\xcf\x1a".as_ref()
+	}.test();
+	
+	CipherTestVector {
+		name: "ChaCha20Poly1305",
+		
+		key: b"\x80\x81\x82\x83\x84\x85\x86\x87\x88\x89\x8a\x8b\x8c\x8d\x8e\x8f\x90\x91\x92\x93\x94\x95\x96\x97\x98\x99\x9a\x9b\x9c\x9d\x9e\x9f",
+		nonce: b"\x07\x00\x00\x00\x40\x41\x42\x43",
+		plaintext: b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.".as_ref(),
+		ad: b"\x50\x51\x52\x53\xc0\xc1\xc2\xc3\xc4\xc5\xc6\xc7".as_ref(),
+		ciphertext: b"\x54\x19\x76\xe6\x5b\x6e\x7c\x98\x06\x88\x9f\x0d\x14\x4a\x87\x81\x17\xc7\x77\x4c\x18\x9d\xb0\xfc\x1e\x36\xe5\x83\x08\x0f\x0b\xfe\x56\x08\x4a\xf8\xbe\x38\x16\x82\x3e\xee\x6d\x55\xda\x5d\x14\x8f\x8b\x81\x36\x97\x32\x2f\x28\x7c\x2e\x94\x5e\x51\x49\x54\xf9\xa1\x52\xd1\xb2\x42\x21\xe5\xeb\xd4\x15\xb0\xad\xbb\xc9\x3f\x8a\x7e\x9c\xbf\xec\x47\x98\x30\xeb\x3e\x63\xa7\x69\x46\xb7\x66\x7c\xd4\x0e\xa5\xf5\x49\x95\xa2\x28\xc3\x25\x89\x8a\xf4\x49\x83\xa0\xa4\xcb\x3e\xf4\xac\x85\x82\x11\x37\xf1\x20\x3b\x01\xf0\x93\xf8\x5d\x44\x13".as_ref()
+	}.test();
+	
+	
+	
+	CipherTestVector {
+		name: "XSalsa20Poly1305",
+		
+		key: b"\x1b\x27\x55\x64\x73\xe9\x85\xd4\x62\xcd\x51\x19\x7a\x9a\x46\xc7\x60\x09\x54\x9e\xac\x64\x74\xf2\x06\xc4\xee\x08\x44\xf6\x83\x89",
+		nonce: b"\x69\x69\x6e\xe9\x55\xb6\x2b\x73\xcd\x62\xbd\xa8\x75\xfc\x73\xd6\x82\x19\xe0\x03\x6b\x7a\x0b\x37",
+		plaintext: b"\xbe\x07\x5f\xc5\x3c\x81\xf2\xd5\xcf\x14\x13\x16\xeb\xeb\x0c\x7b\x52\x28\xc5\x2a\x4c\x62\xcb\xd4\x4b\x66\x84\x9b\x64\x24\x4f\xfc\xe5\xec\xba\xaf\x33\xbd\x75\x1a\x1a\xc7\x28\xd4\x5e\x6c\x61\x29\x6c\xdc\x3c\x01\x23\x35\x61\xf4\x1d\xb6\x6c\xce\x31\x4a\xdb\x31\x0e\x3b\xe8\x25\x0c\x46\xf0\x6d\xce\xea\x3a\x7f\xa1\x34\x80\x57\xe2\xf6\x55\x6a\xd6\xb1\x31\x8a\x02\x4a\x83\x8f\x21\xaf\x1f\xde\x04\x89\x77\xeb\x48\xf5\x9f\xfd\x49\x24\xca\x1c\x60\x90\x2e\x52\xf0\xa0\x89\xbc\x76\x89\x70\x40\xe0\x82\xf9\x37\x76\x38\x48\x64\x5e\x07\x05".as_ref(),
+		ad: &[],
+		ciphertext: b"\xf3\xff\xc7\x70\x3f\x94\x00\xe5\x2a\x7d\xfb\x4b\x3d\x33\x05\xd9\x8e\x99\x3b\x9f\x48\x68\x12\x73\xc2\x96\x50\xba\x32\xfc\x76\xce\x48\x33\x2e\xa7\x16\x4d\x96\xa4\x47\x6f\xb8\xc5\x31\xa1\x18\x6a\xc0\xdf\xc1\x7c\x98\xdc\xe8\x7b\x4d\xa7\xf0\x11\xec\x48\xc9\x72\x71\xd2\xc2\x0f\x9b\x92\x8f\xe2\x27\x0d\x6f\xb8\x63\xd5\x17\x38\xb4\x8e\xee\xe3\x14\xa7\xcc\x8a\xb9\x32\x16\x45\x48\xe5\x26\xae\x90\x22\x43\x68\x51\x7a\xcf\xea\xbd\x6b\xb3\x73\x2b\xc0\xe9\xda\x99\x83\x2b\x61\xca\x01\xb6\xde\x56\x24\x4a\x9e\x88\xd5\xf9\xb3\x79\x73\xf6\x22\xa4\x3d\x14\xa6\x59\x9b\x1f\x65\x4c\xb4\x5a\x74\xe3\x55\xa5".as_ref()
+	}.test();
+	
+	CipherTestVector {
+		name: "XSalsa20Poly1305",
+		
+		key: b"\x1b\x27\x55\x64\x73\xe9\x85\xd4\x62\xcd\x51\x19\x7a\x9a\x46\xc7\x60\x09\x54\x9e\xac\x64\x74\xf2\x06\xc4\xee\x08\x44\xf6\x83\x89",
+		nonce: b"\x69\x69\x6e\xe9\x55\xb6\x2b\x73\xcd\x62\xbd\xa8\x75\xfc\x73\xd6\x82\x19\xe0\x03\x6b\x7a\x0b\x37",
+		plaintext: b"".as_ref(),
+		ad: &[],
+		ciphertext: b"\x25\x39\x12\x1d\x8e\x23\x4e\x65\x2d\x65\x1f\xa4\xc8\xcf\xf8\x80".as_ref()
+	}.test();
+	
+	
+	
+	CipherTestVector {
+		name: "XChaCha20Poly1305",
+		
+		key: b"\x1b\x27\x55\x64\x73\xe9\x85\xd4\x62\xcd\x51\x19\x7a\x9a\x46\xc7\x60\x09\x54\x9e\xac\x64\x74\xf2\x06\xc4\xee\x08\x44\xf6\x83\x89",
+		nonce: b"\x69\x69\x6e\xe9\x55\xb6\x2b\x73\xcd\x62\xbd\xa8\x75\xfc\x73\xd6\x82\x19\xe0\x03\x6b\x7a\x0b\x37",
+		plaintext: b"\xbe\x07\x5f\xc5\x3c\x81\xf2\xd5\xcf\x14\x13\x16\xeb\xeb\x0c\x7b\x52\x28\xc5\x2a\x4c\x62\xcb\xd4\x4b\x66\x84\x9b\x64\x24\x4f\xfc\xe5\xec\xba\xaf\x33\xbd\x75\x1a\x1a\xc7\x28\xd4\x5e\x6c\x61\x29\x6c\xdc\x3c\x01\x23\x35\x61\xf4\x1d\xb6\x6c\xce\x31\x4a\xdb\x31\x0e\x3b\xe8\x25\x0c\x46\xf0\x6d\xce\xea\x3a\x7f\xa1\x34\x80\x57\xe2\xf6\x55\x6a\xd6\xb1\x31\x8a\x02\x4a\x83\x8f\x21\xaf\x1f\xde\x04\x89\x77\xeb\x48\xf5\x9f\xfd\x49\x24\xca\x1c\x60\x90\x2e\x52\xf0\xa0\x89\xbc\x76\x89\x70\x40\xe0\x82\xf9\x37\x76\x38\x48\x64\x5e\x07\x05".as_ref(),
+		ad: &[],
+		ciphertext: b"\x0c\x61\xfc\xff\xbc\x3f\xc8\xd3\xaa\x74\x64\xb9\x1a\xb3\x53\x74\xbf\x8a\xf3\x19\x85\x85\xe5\x5d\x9c\xb0\x7e\xdc\xd1\xe5\xa6\x95\x26\x54\x7f\xbd\x0f\x2c\x64\x2e\x9e\xe9\x6e\x19\x46\x20\x31\xf1\x03\x2f\x1c\xd8\x62\xbb\x95\x29\x00\x10\x3c\x06\xac\x16\x34\x4d\x7f\x9c\x9d\xf0\xfe\xaa\xf5\xa7\x33\xde\xa7\xea\x2d\xf7\x0a\x61\x99\x36\xfc\xc5\x50\x1d\xe7\x5c\x5d\x11\x2e\x8a\xbd\x75\x73\xc4\x61\xad\xa2\x9e\xc0\x16\xd1\x31\xaa\x55\x78\x04\x32\x00\x11\xff\x6d\x94\x09\x25\x81\xce\xea\x1b\xad\x3c\xf0\xd6\x51\x93\x88\x02\xca\x86\x7c\xd5\x2b\xbe\x50\xc2\xda\x11\x61\xcb\x09\x51\x44\x07\x60\x99\x20".as_ref()
+	}.test();
+	
+	CipherTestVector {
+		name: "XChaCha20Poly1305",
+		
+		key: b"\x1b\x27\x55\x64\x73\xe9\x85\xd4\x62\xcd\x51\x19\x7a\x9a\x46\xc7\x60\x09\x54\x9e\xac\x64\x74\xf2\x06\xc4\xee\x08\x44\xf6\x83\x89",
+		nonce: b"\x69\x69\x6e\xe9\x55\xb6\x2b\x73\xcd\x62\xbd\xa8\x75\xfc\x73\xd6\x82\x19\xe0\x03\x6b\x7a\x0b\x37",
+		plaintext: b"".as_ref(),
+		ad: &[],
+		ciphertext: b"\x89\x46\xd8\xf1\x8f\x31\x34\x65\xc8\x62\xa0\x87\x82\x64\x82\x48".as_ref()
+	}.test();
+	
+	
+	
 	CipherTestVector {
 		name: "ChaCha20Ietf",
 		
@@ -309,6 +567,116 @@ fn test() {
 	
 	
 	
+	CipherTestVector {
+		name: "XChaCha20",
+		
+		key: b"\x80\x81\x82\x83\x84\x85\x86\x87\x88\x89\x8a\x8b\x8c\x8d\x8e\x8f\x90\x91\x92\x93\x94\x95\x96\x97\x98\x99\x9a\x9b\x9c\x9d\x9e\x9f",
+		nonce: b"\x40\x41\x42\x43\x44\x45\x46\x47\x48\x49\x4a\x4b\x4c\x4d\x4e\x4f\x50\x51\x52\x53\x54\x55\x56\x57",
+		plaintext: [0u8; 64].as_ref(),
+		ad: &[],
+		ciphertext: b"\x7b\x19\x1f\x80\xf3\x61\xf0\x99\x09\x4f\x6f\x4b\x8f\xb9\x7d\xf8\x47\xcc\x68\x73\xa8\xf2\xb1\x90\xdd\x73\x80\x71\x83\xf9\x07\xd5\xa1\xcb\x27\x38\x5b\x00\x32\x9f\x7d\xdc\x12\x70\x59\xd6\x88\x25\x51\xa1\x20\xe7\x63\x13\x52\xe9\xb0\x38\x15\x72\xe9\x50\x15\x5a".as_ref()
+	}.test();
+	
+	CipherTestVector {
+		name: "XChaCha20",
+		
+		key: b"\x80\x81\x82\x83\x84\x85\x86\x87\x88\x89\x8a\x8b\x8c\x8d\x8e\x8f\x90\x91\x92\x93\x94\x95\x96\x97\x98\x99\x9a\x9b\x9c\x9d\x9e\x9f",
+		nonce: b"\x40\x41\x42\x43\x44\x45\x46\x47\x48\x49\x4a\x4b\x4c\x4d\x4e\x4f\x50\x51\x52\x53\x54\x55\x56\x57",
+		plaintext: b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.".as_ref(),
+		ad: &[],
+		ciphertext: b"\x37\x78\x7b\xe9\x96\x12\xd0\xf8\x67\x2b\x4f\x0c\xea\xd7\x09\x94\x22\xa1\x0d\x1d\x88\x9d\xd7\xb0\xa9\x1b\xe5\x51\xe0\x95\x66\xa6\xd2\xeb\x48\x5e\x7b\x27\x0b\xa6\x47\xfc\x5b\x16\x79\x9f\xa8\x46\x3e\xd4\x4c\x83\x43\x7c\x34\x8f\xd5\x4a\x35\x0b\x86\x25\x35\x35\x9f\x60\x0a\xd4\x34\x9e\x91\x7a\x8f\x7b\x07\xf3\x90\xc1\xef\x75\x46\x2f\x17\x4e\x63\x31\xe8\x99\xb8\xdf\xd9\x2c\x31\x20\x63\xbb\x63\x4e\x75\x18\x45\x4d\xe8\x12\x44\xbf\x85\x69\x0c\xf6\x7e\x33\xb5\x3f".as_ref()
+	}.test();
+	
+	
+	
+	CipherTestVector {
+		name: "XSalsa20",
+		
+		key: b"\x80\x81\x82\x83\x84\x85\x86\x87\x88\x89\x8a\x8b\x8c\x8d\x8e\x8f\x90\x91\x92\x93\x94\x95\x96\x97\x98\x99\x9a\x9b\x9c\x9d\x9e\x9f",
+		nonce: b"\x40\x41\x42\x43\x44\x45\x46\x47\x48\x49\x4a\x4b\x4c\x4d\x4e\x4f\x50\x51\x52\x53\x54\x55\x56\x57",
+		plaintext: [0u8; 64].as_ref(),
+		ad: &[],
+		ciphertext: b"\xbd\xfc\xb0\xc1\x3e\xcf\x47\x4e\xc2\x1f\x83\xb9\xb0\x6e\x76\x42\x90\x3d\xb3\x5d\x52\x73\x8c\x2e\x90\xca\x24\xcb\x86\x10\x5b\x27\xe2\xa5\x34\xcd\x4a\xaa\xcc\x9f\x16\xf6\x90\x24\xa7\x00\x11\x06\x4b\x44\x97\x61\x32\x92\xa7\x9e\x58\x89\xe6\x17\xec\xc0\x84\x99".as_ref()
+	}.test();
+	
+	CipherTestVector {
+		name: "XSalsa20",
+		
+		key: b"\x80\x81\x82\x83\x84\x85\x86\x87\x88\x89\x8a\x8b\x8c\x8d\x8e\x8f\x90\x91\x92\x93\x94\x95\x96\x97\x98\x99\x9a\x9b\x9c\x9d\x9e\x9f",
+		nonce: b"\x40\x41\x42\x43\x44\x45\x46\x47\x48\x49\x4a\x4b\x4c\x4d\x4e\x4f\x50\x51\x52\x53\x54\x55\x56\x57",
+		plaintext: b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.".as_ref(),
+		ad: &[],
+		ciphertext: b"\xf1\x9d\xd4\xa8\x5b\xbc\x67\x2f\xac\x7b\xa3\xfe\xd5\x00\x02\x2e\xf5\x50\xd6\x33\x72\x1c\xea\x0e\xe4\xa2\x41\xeb\xe5\x7c\x3a\x54\x91\x85\x5b\xab\x6a\x8d\xf5\xa6\x2c\xd6\xd9\x42\x87\x49\x31\x65\x24\x31\xfb\x05\x12\xfd\xc1\xf8\x3d\xfb\xc6\x6e\x83\xb5\xa4\xf6\x42\x7a\x81\x7c\xe9\x82\xd4\xf2\x69\xd0\x43\x46\x8d\xbf\x30\x22\x2d\x7a\x17\xcc\xdd\x05\xd6\x55\x68\xe9\x0f\x81\xc1\xb0\x6e\xe9\xff\x83\xdf\x1f\x1a\xca\xaa\x5e\x9b\xba\x43\xe4\xc9\x9a\xc3\x09\x4e\x2e".as_ref()
+	}.test();
+	
+	
+	
+	CipherTestVector {
+		name: "Salsa20",
+		
+		key: b"\x80\x81\x82\x83\x84\x85\x86\x87\x88\x89\x8a\x8b\x8c\x8d\x8e\x8f\x90\x91\x92\x93\x94\x95\x96\x97\x98\x99\x9a\x9b\x9c\x9d\x9e\x9f",
+		nonce: b"\x40\x41\x42\x43\x44\x45\x46\x47",
+		plaintext: [0u8; 64].as_ref(),
+		ad: &[],
+		ciphertext: b"\x87\x58\xd6\x73\x65\x4d\x35\xbd\x91\x9a\xc2\x73\x04\x33\xee\x97\xab\xb7\x64\x24\x96\x80\x14\x57\x8b\x62\x4f\x28\x96\xc5\xa8\x96\x42\x38\x3d\x82\x9a\x1b\xa2\xd4\x2a\x4e\x10\x3b\x56\x4d\xdc\x6e\xe9\x34\x03\xf8\x1a\xf7\x8c\xc0\xd0\xeb\x8a\x62\x67\x37\xc9\xbe".as_ref()
+	}.test();
+	
+	CipherTestVector {
+		name: "Salsa20",
+		
+		key: b"\x80\x81\x82\x83\x84\x85\x86\x87\x88\x89\x8a\x8b\x8c\x8d\x8e\x8f\x90\x91\x92\x93\x94\x95\x96\x97\x98\x99\x9a\x9b\x9c\x9d\x9e\x9f",
+		nonce: b"\x40\x41\x42\x43\x44\x45\x46\x47",
+		plaintext: b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.".as_ref(),
+		ad: &[],
+		ciphertext: b"\xcb\x39\xb2\x1a\x00\x3e\x15\xdc\xff\xfe\xe2\x34\x61\x5d\x9a\xfb\xce\xda\x01\x4a\xb6\xef\x72\x77\xff\x0a\x2a\x08\xf5\xa9\xc9\xe5\x31\x18\x52\xe4\xba\x3c\x9b\xed\x10\x6e\x59\x5d\x76\x04\xfc\x0d\x86\x41\x6f\x9c\x3a\x98\xea\xa6\xb5\x99\xaa\x1b\x08\x42\xe9\xd1\x7a\xe0\x5d\xfe\x79\xbd\xdd\x05\xc0\xc8\x54\xe4\x99\xd8\xde\x27\x8f\x57\x9b\x20\xad\x91\x86\xaf\x24\xd2\x1d\x75\x40\x44\x76\x87\x81\x12\xb9\x0e\xc6\xa2\xf5\xaf\xd7\xc9\x80\x99\x96\x1e\xa9\x9b\xb5\xd8".as_ref()
+	}.test();
+	
+	
+	
+	CipherTestVector {
+		name: "Salsa2012",
+		
+		key: b"\x80\x81\x82\x83\x84\x85\x86\x87\x88\x89\x8a\x8b\x8c\x8d\x8e\x8f\x90\x91\x92\x93\x94\x95\x96\x97\x98\x99\x9a\x9b\x9c\x9d\x9e\x9f",
+		nonce: b"\x40\x41\x42\x43\x44\x45\x46\x47",
+		plaintext: [0u8; 64].as_ref(),
+		ad: &[],
+		ciphertext: b"\x28\x06\xdf\x96\x3f\x2c\xd5\x2d\x47\xa6\x51\xfb\xc5\x8e\x79\x52\x1a\x56\x5f\xdb\xe9\x09\x1e\x28\xf2\xf4\xac\x49\xf2\x74\xe6\xcb\x1b\x76\x21\xec\x17\xc0\x75\xf5\x12\x1d\x1c\x33\x28\x16\xd9\x49\x3d\x4d\x4c\x9d\x1b\x26\xe5\x00\x75\x9e\xd4\x18\xa1\xb1\xf9\xad".as_ref()
+	}.test();
+	
+	CipherTestVector {
+		name: "Salsa2012",
+		
+		key: b"\x80\x81\x82\x83\x84\x85\x86\x87\x88\x89\x8a\x8b\x8c\x8d\x8e\x8f\x90\x91\x92\x93\x94\x95\x96\x97\x98\x99\x9a\x9b\x9c\x9d\x9e\x9f",
+		nonce: b"\x40\x41\x42\x43\x44\x45\x46\x47",
+		plaintext: b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.".as_ref(),
+		ad: &[],
+		ciphertext: b"\x64\x67\xbb\xff\x5a\x5f\xf5\x4c\x29\xc2\x71\xbc\xa0\xe0\x0d\x3e\x7f\x3b\x3a\xb5\xc9\x66\x78\x08\x86\x9c\xc9\x69\x91\x18\x87\xb8\x68\x56\x4e\x8a\x37\xe7\x4c\xcc\x28\x3d\x55\x55\x08\x5f\xf9\x2a\x52\x38\x20\xf9\x3b\x49\x83\x66\x10\xec\xf4\x61\xce\xc4\xd9\xc2\x61\x73\xd6\x0b\xfb\xec\x38\x36\xa5\x7e\x07\x1f\xfa\xa6\xd4\x4b\x94\xb8\xf8\x55\xb6\x69\xeb\xee\x06\x86\xbc\x26\x92\x26\x28\xbe\x76\x5e\x62\xdf\x66\x75\xe8\xec\x39\x45\xdd\x5c\x85\x0b\x87\x87\x15\x79".as_ref()
+	}.test();
+	
+	
+	
+	CipherTestVector {
+		name: "Salsa208",
+		
+		key: b"\x80\x81\x82\x83\x84\x85\x86\x87\x88\x89\x8a\x8b\x8c\x8d\x8e\x8f\x90\x91\x92\x93\x94\x95\x96\x97\x98\x99\x9a\x9b\x9c\x9d\x9e\x9f",
+		nonce: b"\x40\x41\x42\x43\x44\x45\x46\x47",
+		plaintext: [0u8; 64].as_ref(),
+		ad: &[],
+		ciphertext: b"\x81\x02\xd5\x8b\xd4\x2e\xc9\x91\x01\xa4\xa4\xf8\x58\xc7\x7c\x69\x49\x15\x0b\x6c\xce\x19\x1e\x29\x1c\xd4\x51\x00\xfe\x1e\x2e\x40\xb0\x14\x50\xa3\x5d\x27\x9c\xba\x93\x0d\x68\x63\x77\x99\x5b\x81\x5d\xf6\xd0\x57\x65\xf9\xe3\xdc\xd6\xec\x94\xa2\x63\xb2\xa8\xc5".as_ref()
+	}.test();
+	
+	CipherTestVector {
+		name: "Salsa208",
+		
+		key: b"\x80\x81\x82\x83\x84\x85\x86\x87\x88\x89\x8a\x8b\x8c\x8d\x8e\x8f\x90\x91\x92\x93\x94\x95\x96\x97\x98\x99\x9a\x9b\x9c\x9d\x9e\x9f",
+		nonce: b"\x40\x41\x42\x43\x44\x45\x46\x47",
+		plaintext: b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.".as_ref(),
+		ad: &[],
+		ciphertext: b"\xcd\x63\xb1\xe2\xb1\x5d\xe9\xf0\x6f\xc0\x84\xbf\x3d\xa9\x08\x05\x2c\x78\x6e\x02\xee\x76\x78\x09\x68\xbc\x34\x20\x9d\x72\x4f\x33\xc3\x34\x3f\xc5\x7d\x00\xa5\x83\xa9\x2d\x21\x05\x57\xd0\x7b\xe2\x32\x83\xbc\x33\x45\x96\x85\xba\xb3\x9e\xb4\xdb\x0c\xc7\x88\xaa\x0f\x9c\x3f\x0a\xb5\x3e\x73\x49\xe4\x84\x75\x2d\x50\xa4\x29\x8e\xee\x60\x16\xab\x7e\x5a\xd2\x22\x78\x66\x04\x85\x89\x31\xa2\x02\x6d\x02\x1d\xd3\x98\x39\xb3\xe8\x63\x05\xfc\x67\xa9\xa7\x87\xe7\x6a\x78".as_ref()
+	}.test();
+	
+	
+	
 	AeadErrorTestVector {
 		name: "Aes256Gcm",
 		
@@ -364,4 +732,389 @@ fn test() {
 		ad: b"1".as_ref(),
 		ciphertext: b"\xae\x49\xda\x69\x34\xcb\x77\x82\x2c\x83\xef\x98\x52\xe4\x6c\x9e\xda\xc9\xc8\x41\xc1\x68\x37\x9d\xcf\x8f\x2b\xb8\xe2\x2d\x6d\xa2".as_ref()
 	}.test();
-}
\ No newline at end of file
+	
+	
+	
+	AeadErrorTestVector {
+		name: "XChaCha20Poly1305Ietf",
+		
+		key: b"\x80\x81\x82\x83\x84\x85\x86\x87\x88\x89\x8a\x8b\x8c\x8d\x8e\x8f\x90\x91\x92\x93\x94\x95\x96\x97\x98\x99\x9a\x9b\x9c\x9d\x9e\x9f",
+		nonce: b"\x40\x41\x42\x43\x44\x45\x46\x47\x48\x49\x4a\x4b\x4c\x4d\x4e\x4f\x50\x51\x52\x53\x54\x55\x56\x57",
+		ad: b"\x50\x51\x52\x53\xc0\xc1\xc2\xc3\xc4\xc5\xc6\xc7".as_ref(),
+		ciphertext: b"\xbd\x6d\x17\x9d\x3e\x83\xd4\x3b\x95\x76\xff\x94\x93\xc0\xe9\x39\x57\x2a\x17\x00\x25\x2b\xfa\xcc\xbe\xd2\x90\x2c\x21\x39\x6c\xbb\x73\x1c\x7f\x1b\x0b\x4a\xa6\x44\x0b\xf3\xa8\x2f\x4e\xda\x7e\x39\xae\x64\xc6\x70\x8c\x54\xc2\x16\xcb\x96\xb7\x2e\x12\x13\xb4\x52\x2f\x8c\x9b\xa4\x0d\xb5\xd9\x45\xb1\x1b\x69\xb9\x82\xc1\xbb\x9e\x3f\x3f\xac\x2b\xc3\x69\x48\x8f\x76\xb2\x38\x35\x65\xd3\xff\xf9\x21\xf9\x66\x4c\x97\x63\x7d\xa9\x76\x88\x12\xf6\x15\xc6\x8b\x13\xb5\x2e\xc0\x87\x59\x24\xc1\xc7\x98\x79\x47\xde\xaf\xd8\x78\x0a\xcf\x49".as_ref()
+	}.test();
+	
+	AeadErrorTestVector {
+		name: "XChaCha20Poly1305Ietf",
+		
+		key: b"\x80\x81\x82\x83\x84\x85\x86\x87\x88\x89\x8a\x8b\x8c\x8d\x8e\x8f\x90\x91\x92\x93\x94\x95\x96\x97\x98\x99\x9a\x9b\x9c\x9d\x9e\x9f",
+		nonce: b"\x40\x41\x42\x43\x44\x45\x46\x47\x48\x49\x4a\x4b\x4c\x4d\x4e\x4f\x50\x51\x52\x53\x54\x55\x56\x57",
+		ad: b"2".as_ref(),
+		ciphertext: b"\xc0\x3e\x40\xc0\x6e\xc6\xc3\x62\xc2\x73\x15\xb0\x92\xcb\xfb\x65\xb6\xe9\xad\x42\x99\xe1\x46\x03\x08\xb7\xd1\xb1\x4a\x32\x2d\x5a".as_ref()
+	}.test();
+	
+	
+	
+	AeadErrorTestVector {
+		name: "ChaCha20Poly1305",
+		
+		key: b"\x42\x90\xbc\xb1\x54\x17\x35\x31\xf3\x14\xaf\x57\xf3\xbe\x3b\x50\x06\xda\x37\x1e\xce\x27\x2a\xfa\x1b\x5d\xbd\xd1\x10\x0a\x10\x07",
+		nonce: b"\xcd\x7c\xf6\x7b\xe3\x9c\x79\x4a",
+		ad: b"\x87\xe2\x29\xd4\x50\x08\x45\xa0\x79\xc1".as_ref(),
+		ciphertext: b"\xe3\xe4\x46\xf7\xed\xe9\xa1\x9b\x62\xa4\x67\x7d\xab\xf4\xe3\xd2\x4b\x87\x6b\xb2\x84\x75\x38\x96\xe1\xd6".as_ref()
+	}.test();
+	
+	AeadErrorTestVector {
+		name: "ChaCha20Poly1305",
+		
+		key: b"\x80\x81\x82\x83\x84\x85\x86\x87\x88\x89\x8a\x8b\x8c\x8d\x8e\x8f\x90\x91\x92\x93\x94\x95\x96\x97\x98\x99\x9a\x9b\x9c\x9d\x9e\x9f",
+		nonce: b"\x07\x00\x00\x00\x40\x41\x42\x43",
+		ad: b"\x50\x51\x52\x53\xc0\xc1\xc2\xc3\xc4\xc5\xc6\xc7".as_ref(),
+		ciphertext: b"\x54\x19\x76\xe6\x5b\x6e\x7c\x98\x06\x88\x9f\x0d\x14\x4a\x87\x81\x17\xc7\x77\x4c\x00\x9d\xb0\xfc\x1e\x36\xe5\x83\x08\x0f\x0b\xfe\x56\x08\x4a\xf8\xbe\x38\x16\x82\x3e\xee\x6d\x55\xda\x5d\x14\x8f\x8b\x81\x36\x97\x32\x2f\x28\x7c\x2e\x94\x5e\x51\x49\x54\xf9\xa1\x52\xd1\xb2\x42\x21\xe5\xeb\xd4\x15\xb0\xad\xbb\xc9\x3f\x8a\x7e\x9c\xbf\xec\x47\x98\x30\xeb\x3e\x63\xa7\x69\x46\xb7\x66\x7c\xd4\x0e\xa5\xf5\x49\x95\xa2\x28\xc3\x25\x89\x8a\xf4\x49\x83\xa0\xa4\xcb\x3e\xf4\xac\x85\x82\x11\x37\xf1\x20\x3b\x01\xf0\x93\xf8\x5d\x44\x13".as_ref()
+	}.test();
+	
+	
+	
+	ErrorTestVector {
+		name: "XSalsa20Poly1305",
+		
+		key: b"\x1b\x27\x55\x64\x73\xe9\x85\xd4\x62\xcd\x51\x19\x7a\x9a\x46\xc7\x60\x09\x54\x9e\xac\x64\x74\xf2\x06\xc4\xee\x08\x44\xf6\x83\x89",
+		nonce: b"\x69\x69\x6e\xe9\x55\xb6\x2b\x73\xcd\x62\xbd\xa8\x75\xfc\x73\xd6\x82\x19\xe0\x03\x6b\x7a\x0b\x37",
+		ciphertext: b"\xf3\xff\xc6\x70\x3f\x94\x00\xe5\x2a\x7d\xfb\x4b\x3d\x33\x05\xd9\x8e\x99\x3b\x9f\x48\x68\x12\x73\xc2\x96\x50\xba\x32\xfc\x76\xce\x48\x33\x2e\xa7\x16\x4d\x96\xa4\x47\x6f\xb8\xc5\x31\xa1\x18\x6a\xc0\xdf\xc1\x7c\x98\xdc\xe8\x7b\x4d\xa7\xf0\x11\xec\x48\xc9\x72\x71\xd2\xc2\x0f\x9b\x92\x8f\xe2\x27\x0d\x6f\xb8\x63\xd5\x17\x38\xb4\x8e\xee\xe3\x14\xa7\xcc\x8a\xb9\x32\x16\x45\x48\xe5\x26\xae\x90\x22\x43\x68\x51\x7a\xcf\xea\xbd\x6b\xb3\x73\x2b\xc0\xe9\xda\x99\x83\x2b\x61\xca\x01\xb6\xde\x56\x24\x4a\x9e\x88\xd5\xf9\xb3\x79\x73\xf6\x22\xa4\x3d\x14\xa6\x59\x9b\x1f\x65\x4c\xb4\x5a\x74\xe3\x55\xa5".as_ref()
+	}.test();
+	
+	ErrorTestVector {
+		name: "XChaCha20Poly1305",
+		
+		key: b"\x1b\x27\x55\x64\x73\xe9\x85\xd4\x62\xcd\x51\x19\x7a\x9a\x46\xc7\x60\x09\x54\x9e\xac\x64\x74\xf2\x06\xc4\xee\x08\x44\xf6\x83\x89",
+		nonce: b"\x69\x69\x6e\xe9\x55\xb6\x2b\x73\xcd\x62\xbd\xa8\x75\xfc\x73\xd6\x82\x19\xe0\x03\x6b\x7a\x0b\x37",
+		ciphertext: b"\x0c\x61\xfc\xff\xbc\x3f\xc8\xd3\xaa\x74\x64\xb9\x1a\xb3\x53\x74\xbe\x8a\xf3\x19\x85\x85\xe5\x5d\x9c\xb0\x7e\xdc\xd1\xe5\xa6\x95\x26\x54\x7f\xbd\x0f\x2c\x64\x2e\x9e\xe9\x6e\x19\x46\x20\x31\xf1\x03\x2f\x1c\xd8\x62\xbb\x95\x29\x00\x10\x3c\x06\xac\x16\x34\x4d\x7f\x9c\x9d\xf0\xfe\xaa\xf5\xa7\x33\xde\xa7\xea\x2d\xf7\x0a\x61\x99\x36\xfc\xc5\x50\x1d\xe7\x5c\x5d\x11\x2e\x8a\xbd\x75\x73\xc4\x61\xad\xa2\x9e\xc0\x16\xd1\x31\xaa\x55\x78\x04\x32\x00\x11\xff\x6d\x94\x09\x25\x81\xce\xea\x1b\xad\x3c\xf0\xd6\x51\x93\x88\x02\xca\x86\x7c\xd5\x2b\xbe\x50\xc2\xda\x11\x61\xcb\x09\x51\x44\x07\x60\x99\x20".as_ref()
+	}.test();
+}
+
+
+#[test]
+fn test_availability() {
+	// Non-AES implementations are always available
+	let names = [
+		"ChaCha20Poly1305Ietf", "XChaCha20Poly1305Ietf", "ChaCha20Poly1305",
+		"ChaCha20Poly1305IetfCommitting", "XChaCha20Poly1305IetfCommitting", "XChaCha20Siv",
+		"XSalsa20Poly1305", "XChaCha20Poly1305",
+		"ChaCha20Ietf", "XChaCha20", "XSalsa20", "Salsa20", "Salsa2012", "Salsa208"
+	];
+	for name in names.iter() {
+		assert!(Ciphers::from_name(name).unwrap().is_available());
+	}
+	
+	// AES-256-GCM depends on the hardware
+	match Ciphers::Aes256Gcm.is_available() {
+		true => {
+			assert_eq!(Ciphers::from_name("Aes256Gcm").unwrap(), Ciphers::Aes256Gcm);
+			assert!(Ciphers::Aes256Gcm.aead_cipher().is_ok());
+			assert_eq!(Ciphers::best_aead(), Ciphers::Aes256Gcm);
+		},
+		false => {
+			assert_eq!(Ciphers::from_name("Aes256Gcm").unwrap_err(), LibsodiumError::Unsupported);
+			assert_eq!(Ciphers::Aes256Gcm.aead_cipher().err(), Some(LibsodiumError::Unsupported));
+			assert_eq!(Ciphers::best_aead(), Ciphers::ChaCha20Poly1305Ietf);
+			
+			let err = Ciphers::Aes256Gcm.cipher()
+				.encrypt(&mut [0; 16], 0, &[0; 32], &[0; 12]).unwrap_err();
+			compare_err!(err, LibsodiumError::Unsupported);
+		}
+	}
+	assert!(Ciphers::best_aead().aead_cipher().is_ok());
+}
+
+
+#[test]
+fn test_detached_errors() {
+	for name in ["ChaCha20Poly1305Ietf", "XChaCha20Poly1305Ietf", "ChaCha20Poly1305"].iter() {
+		let cipher = Ciphers::from_name(name).unwrap().detached_aead_cipher().unwrap();
+		let info = cipher.info();
+		let (key, nonce) = (vec![0; info.key_len], vec![0; info.nonce_len]);
+		
+		// Test tag lengths
+		let err = cipher.seal_detached(&mut [0; 8], 8, &mut [0; 15], &[], &key, &nonce).unwrap_err();
+		compare_err!(err, LibsodiumError::ApiMisuse("Tag buffer is too small"));
+		let err = cipher.open_detached(&mut [0; 8], 8, &[0; 17], &[], &key, &nonce).unwrap_err();
+		compare_err!(err, LibsodiumError::ApiMisuse("Invalid tag length"));
+		
+		// Test buffer lengths
+		let err = cipher.seal_detached(&mut [0; 8], 9, &mut [0; 16], &[], &key, &nonce).unwrap_err();
+		compare_err!(err, LibsodiumError::ApiMisuse("Buffer is too small"));
+		let err = cipher.open_detached(&mut [0; 8], 9, &[0; 16], &[], &key, &nonce).unwrap_err();
+		compare_err!(err, LibsodiumError::ApiMisuse("Buffer is too small"));
+	}
+	
+	// Stream ciphers and secretboxes are neither AEAD ciphers nor do they have a detached mode
+	let ciphers = [Ciphers::ChaCha20Ietf, Ciphers::XSalsa20Poly1305, Ciphers::XChaCha20Poly1305];
+	for cipher in ciphers.iter() {
+		assert_eq!(cipher.aead_cipher().err(), Some(LibsodiumError::Unsupported));
+		assert_eq!(cipher.detached_aead_cipher().err(), Some(LibsodiumError::Unsupported));
+	}
+}
+
+
+#[test]
+fn test_detached_empty_ad() {
+	for name in ["ChaCha20Poly1305Ietf", "XChaCha20Poly1305Ietf", "ChaCha20Poly1305"].iter() {
+		let cipher = Ciphers::from_name(name).unwrap();
+		let (aead, detached) = (cipher.aead_cipher().unwrap(), cipher.detached_aead_cipher().unwrap());
+		let info = detached.info();
+		let tag_len = info.aead_tag_len.unwrap();
+		let (key, nonce) = (vec![0x2a; info.key_len], vec![0x07; info.nonce_len]);
+		let plaintext = b"Detached with empty AD";
+		
+		// The detached ciphertext and tag must match the combined ciphertext
+		let mut combined = plaintext.to_vec();
+		combined.resize(plaintext.len() + tag_len, 0);
+		aead.seal(&mut combined, plaintext.len(), &[], &key, &nonce).unwrap();
+		
+		let (mut buf, mut tag) = (plaintext.to_vec(), vec![0; tag_len]);
+		let ciphertext_len = detached.seal_detached(&mut buf, plaintext.len(), &mut tag, &[], &key, &nonce)
+			.unwrap();
+		assert_eq!(&buf[..ciphertext_len], &combined[..plaintext.len()]);
+		assert_eq!(tag.as_slice(), &combined[plaintext.len()..]);
+		
+		// Opening must fail with a non-empty AD and succeed with an empty one
+		let err = detached.open_detached(&mut buf.clone(), ciphertext_len, &tag, b"AD", &key, &nonce)
+			.unwrap_err();
+		compare_err!(err, LibsodiumError::InvalidData);
+		let plaintext_len = detached.open_detached(&mut buf, ciphertext_len, &tag, &[], &key, &nonce)
+			.unwrap();
+		assert_eq!(&buf[..plaintext_len], plaintext.as_ref());
+	}
+}
+
+
+#[test]
+fn test_aes256gcm_state() {
+	// Skip the test if AES-256-GCM is not available
+	if !Ciphers::Aes256Gcm.is_available() {
+		assert_eq!(Aes256GcmState::new(&[0; 32]).err(), Some(LibsodiumError::Unsupported));
+		return;
+	}
+	
+	// Test key length
+	assert_eq!(
+		Aes256GcmState::new(&[0; 31]).err(),
+		Some(LibsodiumError::ApiMisuse("Invalid key length"))
+	);
+	
+	// Create the state and the reference cipher
+	let key = b"\x73\xad\x7b\xbb\xbc\x64\x0c\x84\x5a\x15\x0f\x67\xd0\x58\xb2\x79\x84\x93\x70\xcd\x2c\x1f\x3c\x67\xc4\xdd\x6c\x86\x92\x13\xe1\x3a";
+	let nonce = b"\xa3\x30\xa1\x84\xfc\x24\x58\x12\xf4\x82\x0c\xaa";
+	let (plaintext, ad) = (b"\xf0\x53\x5f\xe2\x11", b"\xe9\x14\x28\xbe\x04");
+	let ciphertext = b"\xe9\xb8\xa8\x96\xda\x91\x15\xed\x79\xf2\x6a\x03\x0c\x14\x94\x7b\x3e\x45\x4d\xb9\xe7";
+	let state = Aes256GcmState::new(key).unwrap();
+	
+	// Test combined mode
+	let mut buf = plaintext.to_vec();
+	buf.resize(ciphertext.len(), 0);
+	let ciphertext_len = state.seal(&mut buf, plaintext.len(), ad, nonce).unwrap();
+	assert_eq!(&buf[..ciphertext_len], ciphertext.as_ref());
+	let plaintext_len = state.open(&mut buf, ciphertext_len, ad, nonce).unwrap();
+	assert_eq!(&buf[..plaintext_len], plaintext.as_ref());
+	
+	// Test detached mode
+	let (mut buf, mut tag) = (plaintext.to_vec(), [0; 16]);
+	let ciphertext_len = state.seal_detached(&mut buf, plaintext.len(), &mut tag, ad, nonce).unwrap();
+	assert_eq!(&buf[..ciphertext_len], &ciphertext[..5]);
+	assert_eq!(tag.as_ref(), &ciphertext[5..]);
+	let plaintext_len = state.open_detached(&mut buf, ciphertext_len, &tag, ad, nonce).unwrap();
+	assert_eq!(&buf[..plaintext_len], plaintext.as_ref());
+	
+	// Test tampered data
+	let mut buf = ciphertext.to_vec();
+	buf[0] ^= 0x01;
+	let err = state.open(&mut buf, ciphertext.len(), ad, nonce).unwrap_err();
+	compare_err!(err, LibsodiumError::InvalidData);
+	let err = state.open_detached(&mut buf[..5].to_vec(), 5, &ciphertext[5..], ad, nonce)
+		.unwrap_err();
+	compare_err!(err, LibsodiumError::InvalidData);
+	
+	// Test parameter checks
+	let err = state.seal(&mut [0; 20], 5, ad, nonce).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Buffer is too small"));
+	let err = state.seal(&mut [0; 21], 5, ad, &nonce[1..]).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Invalid nonce length"));
+	let err = state.open_detached(&mut [0; 5], 5, &[0; 15], ad, nonce).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Invalid tag length"));
+}
+
+
+#[test]
+fn test_seekable() {
+	let data: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+	for name in ["ChaCha20Ietf", "XChaCha20", "XSalsa20", "Salsa20"].iter() {
+		let cipher = Ciphers::from_name(name).unwrap();
+		let (stream, seekable) = (cipher.cipher(), cipher.seekable_cipher().unwrap());
+		let info = stream.info();
+		let (key, nonce) = (vec![0x2a; info.key_len], vec![0x17; info.nonce_len]);
+		
+		// Encrypt the data contiguously
+		let mut contiguous = data.clone();
+		stream.encrypt(&mut contiguous, data.len(), &key, &nonce).unwrap();
+		
+		// Encrypt and decrypt aligned and unaligned ranges
+		let ranges = [
+			(0, 0), (0, 1), (0, 64), (0, 1000), (1, 1), (1, 62), (1, 63), (1, 64), (63, 2),
+			(64, 64), (65, 200), (127, 1), (130, 870), (999, 1), (1000, 0)
+		];
+		for (offset, len) in ranges.iter() {
+			let range = *offset..*offset + *len;
+			
+			let mut buf = data[range.clone()].to_vec();
+			let ciphertext_len = seekable.encrypt_at(&mut buf, *len, &key, &nonce, *offset as u64)
+				.unwrap();
+			assert_eq!(&buf[..ciphertext_len], &contiguous[range.clone()]);
+			
+			let plaintext_len = seekable.decrypt_at(&mut buf, *len, &key, &nonce, *offset as u64)
+				.unwrap();
+			assert_eq!(&buf[..plaintext_len], &data[range]);
+		}
+		
+		// Test parameter checks
+		let err = seekable.encrypt_at(&mut [0; 4], 5, &key, &nonce, 0).unwrap_err();
+		compare_err!(err, LibsodiumError::ApiMisuse("Buffer is too small"));
+		let err = seekable.encrypt_at(&mut [0; 4], 4, &key[1..], &nonce, 0).unwrap_err();
+		compare_err!(err, LibsodiumError::ApiMisuse("Invalid key length"));
+		let err = seekable.encrypt_at(&mut [0; 4], 4, &key, &nonce[1..], 0).unwrap_err();
+		compare_err!(err, LibsodiumError::ApiMisuse("Invalid nonce length"));
+		let err = seekable.encrypt_at(&mut [0; 4], 4, &key, &nonce, u64::MAX - 2).unwrap_err();
+		compare_err!(err, LibsodiumError::ApiMisuse("Offset is too large"));
+	}
+	
+	// Test the end of the 32 bit block counter of ChaCha20Ietf
+	let seekable = Ciphers::ChaCha20Ietf.seekable_cipher().unwrap();
+	let keystream_len = 64 << 32;
+	seekable.encrypt_at(&mut [0; 64], 64, &[0; 32], &[0; 12], keystream_len - 64).unwrap();
+	let err = seekable.encrypt_at(&mut [0; 65], 65, &[0; 32], &[0; 12], keystream_len - 64)
+		.unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Offset is too large"));
+	
+	// Test unsupported ciphers
+	for cipher in [Ciphers::Salsa2012, Ciphers::Salsa208, Ciphers::ChaCha20Poly1305Ietf].iter() {
+		assert_eq!(cipher.seekable_cipher().err(), Some(LibsodiumError::Unsupported));
+	}
+}
+
+
+#[test]
+fn test_committing() {
+	const KEY: &[u8] = b"\x80\x81\x82\x83\x84\x85\x86\x87\x88\x89\x8a\x8b\x8c\x8d\x8e\x8f\x90\x91\x92\x93\x94\x95\x96\x97\x98\x99\x9a\x9b\x9c\x9d\x9e\x9f";
+	const PLAINTEXT: &[u8] = b"Ladies and Gentlemen of the class of '99";
+	const AD: &[u8] = b"\x50\x51\x52\x53\xc0\xc1\xc2\xc3";
+	
+	// Test vectors (`commitment || ciphertext || tag`; generated with Python's BLAKE2b and libsodium)
+	let vectors: [(Ciphers, &[u8], &[u8]); 3] = [
+		(
+			Ciphers::ChaCha20Poly1305IetfCommitting,
+			b"\x40\x41\x42\x43\x44\x45\x46\x47\x48\x49\x4a\x4b",
+			b"\x70\xff\xb0\xc4\x28\xdc\x1b\xf9\x12\xde\x2f\xf8\x8e\xc3\x82\x50\xae\xab\xb0\x15\x60\x6a\x2c\x63\x1c\xb1\x8f\x3d\xcc\x9b\xfc\x9d\xf6\x68\xeb\x05\x1e\x28\x9b\x64\x88\xfc\x21\x05\x01\x73\xb4\x54\xcd\xe3\x1b\xa3\xc2\xe0\x58\x5b\x2c\xfc\xe9\xf9\xdc\xf8\xb5\x84\x83\xa4\x40\xf4\x7d\xdd\x6b\x75\x2f\xcb\x50\x69\xf1\x51\xc3\x29\xeb\x80\xc6\x10\x0e\x47\xc7\xa3"
+		),
+		(
+			Ciphers::XChaCha20Poly1305IetfCommitting,
+			b"\x40\x41\x42\x43\x44\x45\x46\x47\x48\x49\x4a\x4b\x4c\x4d\x4e\x4f\x50\x51\x52\x53\x54\x55\x56\x57",
+			b"\xea\x2e\x88\xd9\x79\x1a\x13\x3f\x16\xc4\xb8\xcf\x56\x59\xfa\xf7\xca\xfc\x39\x23\x2a\x7f\xe1\x9e\x95\x83\x90\xb1\xa2\xe9\xc7\xa2\x79\x44\xc6\x61\x8f\xe0\xc6\x1c\x58\xc1\x9a\x55\x44\x3e\x7e\x6c\x70\x5d\x54\xca\x96\x24\x20\x86\x3c\x74\x7c\xe0\x0a\x66\x41\x67\xfe\xb4\x9b\x3e\x7f\xee\xa2\x18\x88\x89\x4b\x19\x44\xe6\x92\x71\x2c\x24\xec\xcb\xc5\xb5\xf7\x9e"
+		),
+		(
+			Ciphers::Aes256GcmCommitting,
+			b"\x40\x41\x42\x43\x44\x45\x46\x47\x48\x49\x4a\x4b",
+			b"\x70\xff\xb0\xc4\x28\xdc\x1b\xf9\x12\xde\x2f\xf8\x8e\xc3\x82\x50\xae\xab\xb0\x15\x60\x6a\x2c\x63\x1c\xb1\x8f\x3d\xcc\x9b\xfc\x9d\x69\x54\xc8\xc1\xfe\x07\xb7\x26\x56\x9e\x82\x7e\xe1\x67\xfc\x6c\xc8\x2d\x40\x63\xfa\xa2\x33\x06\xf9\xfb\x1b\xb7\x9c\xa1\xba\x5c\x54\x83\x72\x3b\x00\xa4\x8e\xc4\x7b\x29\xca\xf0\x2d\xd1\xe0\xc7\xd2\x26\x6a\xcc\xd3\x4c\x4a\xc8"
+		)
+	];
+	for (cipher, nonce, ciphertext) in vectors.iter().filter(|(cipher, _, _)| cipher.is_available()) {
+		let aead = cipher.aead_cipher().unwrap();
+		let info = aead.info();
+		CipherInfo{ aead_tag_len: Some(48), ..info }.test();
+		
+		// Test sealing and opening
+		let mut buf = PLAINTEXT.to_vec();
+		buf.resize(ciphertext.len(), 0);
+		let ciphertext_len = aead.seal(&mut buf, PLAINTEXT.len(), AD, KEY, nonce).unwrap();
+		assert_eq!(&buf[..ciphertext_len], *ciphertext);
+		let plaintext_len = aead.open(&mut buf, ciphertext_len, AD, KEY, nonce).unwrap();
+		assert_eq!(&buf[..plaintext_len], PLAINTEXT);
+		
+		// A wrong key or a modified commitment must be rejected
+		let err = aead.open(&mut ciphertext.to_vec(), ciphertext.len(), AD, &[0; 32], nonce)
+			.unwrap_err();
+		compare_err!(err, LibsodiumError::InvalidData);
+		let mut buf = ciphertext.to_vec();
+		buf[0] ^= 0x01;
+		let err = aead.open(&mut buf, ciphertext.len(), AD, KEY, nonce).unwrap_err();
+		compare_err!(err, LibsodiumError::InvalidData);
+		let err = aead.open(&mut ciphertext.to_vec(), 47, AD, KEY, nonce).unwrap_err();
+		compare_err!(err, LibsodiumError::InvalidData);
+		
+		// Committing ciphers have no detached mode
+		assert_eq!(cipher.detached_aead_cipher().err(), Some(LibsodiumError::Unsupported));
+	}
+}
+
+
+#[test]
+fn test_siv() {
+	const KEY: &[u8] = b"\x80\x81\x82\x83\x84\x85\x86\x87\x88\x89\x8a\x8b\x8c\x8d\x8e\x8f\x90\x91\x92\x93\x94\x95\x96\x97\x98\x99\x9a\x9b\x9c\x9d\x9e\x9f";
+	const PLAINTEXT: &[u8] = b"Ladies and Gentlemen of the class of '99";
+	
+	CipherInfo {
+		name: "XChaCha20Siv",
+		key_len: 32,
+		nonce_len: 24,
+		aead_tag_len: Some(24)
+	}.test();
+	
+	// Test vectors (`synthetic IV || ciphertext`; generated with Python's BLAKE2b and libsodium)
+	CipherTestVector {
+		name: "XChaCha20Siv",
+		
+		key: KEY,
+		nonce: b"\x40\x41\x42\x43\x44\x45\x46\x47\x48\x49\x4a\x4b\x4c\x4d\x4e\x4f\x50\x51\x52\x53\x54\x55\x56\x57",
+		plaintext: PLAINTEXT,
+		ad: b"",
+		ciphertext: b"\x09\x21\xd0\x1a\x58\x16\x37\x00\x23\x90\xf1\x98\xcd\x97\xcc\xba\x32\xa1\xf8\x61\x6a\xa5\x9a\x7e\x3b\x33\xf2\x96\x09\xee\xa8\xc5\x25\xe8\x03\xe4\x95\x4a\x57\xba\x3f\xd4\x14\x74\x7b\x8e\xcc\x93\x8e\x03\xcc\x4f\xeb\xa9\x02\x81\x61\xb3\x4d\x1d\xb4\xf9\x61\x70"
+	}.test();
+	let vectors: [(&[u8], &[u8], &[u8]); 2] = [
+		(
+			b"\x50\x51\x52\x53", PLAINTEXT,
+			b"\x04\x9a\xf3\xa9\xb2\xdb\x32\x33\x1c\x36\xe9\x47\xae\x30\x15\x8c\x66\x3c\x5c\x06\x11\x42\xd7\x42\x9f\xf2\x69\xd8\x42\x55\xb8\x32\x12\x0a\xc1\xa0\x1f\x09\xd0\xdb\x78\xb7\x5b\xe5\x08\xb0\x8d\x0c\x0c\x45\x77\xe5\x1a\xed\xe7\x78\xbc\xfe\xc4\x52\xf5\x7e\x9e\xef"
+		),
+		(b"", b"", b"\x23\xe2\xcd\x66\x1f\x7b\x76\xd1\xec\x99\x77\x98\x3e\x6c\x3e\x87\xa1\x0f\x47\xfb\x56\x4c\x45\x5e")
+	];
+	
+	// Test sealing and opening without a nonce
+	let cipher = Ciphers::XChaCha20Siv.aead_cipher().unwrap();
+	for (ad, plaintext, ciphertext) in vectors.iter() {
+		for _ in 0..2 {
+			let mut buf = plaintext.to_vec();
+			buf.resize(ciphertext.len(), 0);
+			let ciphertext_len = cipher.seal(&mut buf, plaintext.len(), ad, KEY, &[]).unwrap();
+			assert_eq!(&buf[..ciphertext_len], *ciphertext);
+			let plaintext_len = cipher.open(&mut buf, ciphertext_len, ad, KEY, &[]).unwrap();
+			assert_eq!(&buf[..plaintext_len], *plaintext);
+		}
+	}
+	
+	// Tampered synthetic IVs, ciphertexts, associated data and nonces must be rejected (and the
+	// decrypted plaintext must be erased)
+	let (ad, _, ciphertext) = vectors[0];
+	for i in [0, 23, 24, ciphertext.len() - 1].iter() {
+		let mut buf = ciphertext.to_vec();
+		buf[*i] ^= 0x01;
+		let err = cipher.open(&mut buf, ciphertext.len(), ad, KEY, &[]).unwrap_err();
+		compare_err!(err, LibsodiumError::InvalidData);
+		assert!(buf.iter().all(|b| *b == 0));
+	}
+	let err = cipher.open(&mut ciphertext.to_vec(), ciphertext.len(), b"", KEY, &[]).unwrap_err();
+	compare_err!(err, LibsodiumError::InvalidData);
+	let err = cipher.open(&mut ciphertext.to_vec(), ciphertext.len(), ad, KEY, &[0; 24]).unwrap_err();
+	compare_err!(err, LibsodiumError::InvalidData);
+	let err = cipher.open(&mut ciphertext.to_vec(), 23, ad, KEY, &[]).unwrap_err();
+	compare_err!(err, LibsodiumError::InvalidData);
+	
+	// SIV has no detached mode
+	assert_eq!(Ciphers::XChaCha20Siv.detached_aead_cipher().err(), Some(LibsodiumError::Unsupported));
+}
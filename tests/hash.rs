@@ -0,0 +1,316 @@
+use crypto_api_libsodium::{
+	LibsodiumError, Hashes,
+	hash::{ HashInfo, HashParams }
+};
+use std::io::{ self, Read, Write };
+
+
+/// A helper macro to compare a `Box<dyn Error + 'static>` to a `LibsodiumError`
+macro_rules! compare_err {
+	($err:expr, $expected:expr) => (
+		assert_eq!(*$err.downcast_ref::<LibsodiumError>().unwrap(), $expected)
+	);
+}
+
+
+/// A trait to extend the info-structs with the ability to test them
+trait PropertyTest {
+	fn test(&self);
+}
+impl PropertyTest for HashInfo {
+	fn test(&self) {
+		// Create hash
+		let hash = Hashes::from_name(self.name).unwrap().hash();
+		
+		// Test against `info()`
+		assert_eq!(*self, hash.info());
+		
+		// Test the hash length
+		let params = HashParams::default();
+		let err = hash.hash(&mut vec![0; self.hash_len_min - 1], b"Data", &params).unwrap_err();
+		compare_err!(err, LibsodiumError::ApiMisuse("Buffer is too small"));
+		let err = hash.hash(&mut vec![0; self.hash_len_max + 1], b"Data", &params).unwrap_err();
+		compare_err!(err, LibsodiumError::ApiMisuse("Buffer is too large"));
+		
+		for hash_len in [self.hash_len_min - 1, self.hash_len_max + 1].iter().copied() {
+			let err = hash.state(hash_len, &params).err().unwrap();
+			compare_err!(err, LibsodiumError::ApiMisuse("Invalid hash length"));
+		}
+		let err = hash.state(self.hash_len, &params).unwrap()
+			.finish(&mut vec![0; self.hash_len - 1]).unwrap_err();
+		compare_err!(err, LibsodiumError::ApiMisuse("Buffer is too small"));
+		
+		// Test the parameters
+		let key = vec![0; self.key_len_max + 1];
+		let params = HashParams{ key: Some(&key), ..Default::default() };
+		let err = hash.hash(&mut vec![0; self.hash_len], b"Data", &params).unwrap_err();
+		match self.key_len_max {
+			0 => compare_err!(err, LibsodiumError::Unsupported),
+			_ => compare_err!(err, LibsodiumError::ApiMisuse("Invalid key length"))
+		}
+		
+		let salt = vec![0; self.salt_len.unwrap_or(0) + 1];
+		let params = HashParams{ salt: Some(&salt), ..Default::default() };
+		let err = hash.hash(&mut vec![0; self.hash_len], b"Data", &params).unwrap_err();
+		match self.salt_len {
+			None => compare_err!(err, LibsodiumError::Unsupported),
+			Some(_) => compare_err!(err, LibsodiumError::ApiMisuse("Invalid salt length"))
+		}
+		
+		let personal = vec![0; self.personal_len.unwrap_or(0) + 1];
+		let params = HashParams{ personal: Some(&personal), ..Default::default() };
+		let err = hash.state(self.hash_len, &params).err().unwrap();
+		match self.personal_len {
+			None => compare_err!(err, LibsodiumError::Unsupported),
+			Some(_) => compare_err!(err, LibsodiumError::ApiMisuse("Invalid personalization length"))
+		}
+	}
+}
+
+
+struct HashTestVector {
+	name: &'static str,
+	
+	key: Option<&'static[u8]>,
+	salt: Option<&'static[u8]>,
+	personal: Option<&'static[u8]>,
+	data: &'static[u8],
+	hash: &'static[u8]
+}
+impl HashTestVector {
+	pub fn test(&self) {
+		// Create the hash
+		let hash = Hashes::from_name(self.name).unwrap().hash();
+		let params = HashParams{ key: self.key, salt: self.salt, personal: self.personal };
+		
+		// Compute the hash at once
+		let mut buf = vec![0; self.hash.len()];
+		hash.hash(&mut buf, self.data, &params).unwrap();
+		assert_eq!(buf, self.hash);
+		
+		// Compute the hash incrementally
+		let mut state = hash.state(self.hash.len(), &params).unwrap();
+		assert_eq!(state.hash_len(), self.hash.len());
+		self.data.chunks(7).for_each(|chunk| state.update(chunk));
+		
+		let mut buf = vec![0; self.hash.len() + 1];
+		assert_eq!(state.finish(&mut buf).unwrap(), self.hash.len());
+		assert_eq!(&buf[..self.hash.len()], self.hash);
+		
+		// Compute the hash using `Write`
+		let mut state = hash.state(self.hash.len(), &params).unwrap();
+		state.write_all(self.data).unwrap();
+		
+		let mut buf = vec![0; self.hash.len()];
+		state.finish(&mut buf).unwrap();
+		assert_eq!(buf, self.hash);
+	}
+}
+
+
+/// The deterministic sequence generator from RFC 7693, Appendix E
+fn selftest_seq(len: usize, seed: u32) -> Vec<u8> {
+	let (mut a, mut b) = (0xDEAD4BADu32.wrapping_mul(seed), 1u32);
+	(0..len).map(|_| {
+		let t = a.wrapping_add(b);
+		a = b;
+		b = t;
+		(t >> 24) as u8
+	}).collect()
+}
+
+
+#[test]
+fn test() {
+	HashInfo {
+		name: "Blake2b",
+		hash_len: 64, hash_len_min: 16, hash_len_max: 64,
+		key_len_min: 16, key_len_max: 64,
+		salt_len: Some(16), personal_len: Some(16)
+	}.test();
+	
+	// RFC 7693, Appendix A
+	HashTestVector {
+		name: "Blake2b",
+		key: None, salt: None, personal: None,
+		data: b"abc",
+		hash: b"\xba\x80\xa5\x3f\x98\x1c\x4d\x0d\x6a\x27\x97\xb6\x9f\x12\xf6\xe9\x4c\x21\x2f\x14\x68\x5a\xc4\xb7\x4b\x12\xbb\x6f\xdb\xff\xa2\xd1\x7d\x87\xc5\x39\x2a\xab\x79\x2d\xc2\x52\xd5\xde\x45\x33\xcc\x95\x18\xd3\x8a\xa8\xdb\xf1\x92\x5a\xb9\x23\x86\xed\xd4\x00\x99\x23"
+	}.test();
+	
+	// The following vectors were computed with Python's `hashlib.blake2b`
+	HashTestVector {
+		name: "Blake2b",
+		key: Some(b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f\x10\x11\x12\x13\x14\x15\x16\x17\x18\x19\x1a\x1b\x1c\x1d\x1e\x1f"),
+		salt: None, personal: None,
+		data: b"The quick brown fox jumps over the lazy dog",
+		hash: b"\x78\x02\xa6\x0f\x49\x4c\x67\xed\x7f\x6b\x34\x81\x10\xc4\x24\xaa"
+	}.test();
+	
+	HashTestVector {
+		name: "Blake2b",
+		key: None, salt: Some(b"0123456789abcdef"), personal: Some(b"crypto_api_hash!"),
+		data: b"The quick brown fox jumps over the lazy dog",
+		hash: b"\xad\xd0\x80\x2e\x8a\x90\x66\x41\xc1\xe9\xe9\xc0\xc5\x41\xd2\x84\xc2\x9d\x6e\x38\x40\x22\xed\xef\x8a\x8e\xa4\x93\xb2\xd8\x64\x53\x67\xfa\x5e\x6b\xf3\x07\x6e\x78\x04\xa6\x7d\x2b\x75\xe3\x8e\x07\x26\x23\x73\x95\x82\x24\x70\x5d\x69\x3a\xb1\x27\x82\x67\x6b\x2c"
+	}.test();
+	
+	HashTestVector {
+		name: "Blake2b",
+		key: Some(b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f\x10\x11\x12\x13\x14\x15\x16\x17\x18\x19\x1a\x1b\x1c\x1d\x1e\x1f"),
+		salt: Some(b"0123456789abcdef"), personal: Some(b"crypto_api_hash!"),
+		data: b"The quick brown fox jumps over the lazy dog",
+		hash: b"\xb6\x1c\x1c\xaa\x02\x2b\xd4\x7f\x1a\x8a\x1c\x3b\x72\x7a\x5e\x75\xe3\x17\x85\xe2\x27\x0e\xb3\xf9\x95\x80\x7c\x91\xa5\x4c\x8c\x71"
+	}.test();
+	
+	
+	HashInfo {
+		name: "Sha256",
+		hash_len: 32, hash_len_min: 32, hash_len_max: 32,
+		key_len_min: 0, key_len_max: 0,
+		salt_len: None, personal_len: None
+	}.test();
+	
+	// FIPS 180-2 and NIST CSRC examples
+	HashTestVector {
+		name: "Sha256",
+		key: None, salt: None, personal: None,
+		data: b"abc",
+		hash: b"\xba\x78\x16\xbf\x8f\x01\xcf\xea\x41\x41\x40\xde\x5d\xae\x22\x23\xb0\x03\x61\xa3\x96\x17\x7a\x9c\xb4\x10\xff\x61\xf2\x00\x15\xad"
+	}.test();
+	
+	HashTestVector {
+		name: "Sha256",
+		key: None, salt: None, personal: None,
+		data: b"",
+		hash: b"\xe3\xb0\xc4\x42\x98\xfc\x1c\x14\x9a\xfb\xf4\xc8\x99\x6f\xb9\x24\x27\xae\x41\xe4\x64\x9b\x93\x4c\xa4\x95\x99\x1b\x78\x52\xb8\x55"
+	}.test();
+	
+	HashTestVector {
+		name: "Sha256",
+		key: None, salt: None, personal: None,
+		data: b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+		hash: b"\x24\x8d\x6a\x61\xd2\x06\x38\xb8\xe5\xc0\x26\x93\x0c\x3e\x60\x39\xa3\x3c\xe4\x59\x64\xff\x21\x67\xf6\xec\xed\xd4\x19\xdb\x06\xc1"
+	}.test();
+	
+	HashTestVector {
+		name: "Sha256",
+		key: None, salt: None, personal: None,
+		data: b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmn\
+			hijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu",
+		hash: b"\xcf\x5b\x16\xa7\x78\xaf\x83\x80\x03\x6c\xe5\x9e\x7b\x04\x92\x37\x0b\x24\x9b\x11\xe8\xf0\x7a\x51\xaf\xac\x45\x03\x7a\xfe\xe9\xd1"
+	}.test();
+	
+	HashInfo {
+		name: "Sha512",
+		hash_len: 64, hash_len_min: 64, hash_len_max: 64,
+		key_len_min: 0, key_len_max: 0,
+		salt_len: None, personal_len: None
+	}.test();
+	
+	// FIPS 180-2 and NIST CSRC examples
+	HashTestVector {
+		name: "Sha512",
+		key: None, salt: None, personal: None,
+		data: b"abc",
+		hash: b"\xdd\xaf\x35\xa1\x93\x61\x7a\xba\xcc\x41\x73\x49\xae\x20\x41\x31\x12\xe6\xfa\x4e\x89\xa9\x7e\xa2\x0a\x9e\xee\xe6\x4b\x55\xd3\x9a\x21\x92\x99\x2a\x27\x4f\xc1\xa8\x36\xba\x3c\x23\xa3\xfe\xeb\xbd\x45\x4d\x44\x23\x64\x3c\xe8\x0e\x2a\x9a\xc9\x4f\xa5\x4c\xa4\x9f"
+	}.test();
+	
+	HashTestVector {
+		name: "Sha512",
+		key: None, salt: None, personal: None,
+		data: b"",
+		hash: b"\xcf\x83\xe1\x35\x7e\xef\xb8\xbd\xf1\x54\x28\x50\xd6\x6d\x80\x07\xd6\x20\xe4\x05\x0b\x57\x15\xdc\x83\xf4\xa9\x21\xd3\x6c\xe9\xce\x47\xd0\xd1\x3c\x5d\x85\xf2\xb0\xff\x83\x18\xd2\x87\x7e\xec\x2f\x63\xb9\x31\xbd\x47\x41\x7a\x81\xa5\x38\x32\x7a\xf9\x27\xda\x3e"
+	}.test();
+	
+	HashTestVector {
+		name: "Sha512",
+		key: None, salt: None, personal: None,
+		data: b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+		hash: b"\x20\x4a\x8f\xc6\xdd\xa8\x2f\x0a\x0c\xed\x7b\xeb\x8e\x08\xa4\x16\x57\xc1\x6e\xf4\x68\xb2\x28\xa8\x27\x9b\xe3\x31\xa7\x03\xc3\x35\x96\xfd\x15\xc1\x3b\x1b\x07\xf9\xaa\x1d\x3b\xea\x57\x78\x9c\xa0\x31\xad\x85\xc7\xa7\x1d\xd7\x03\x54\xec\x63\x12\x38\xca\x34\x45"
+	}.test();
+	
+	HashTestVector {
+		name: "Sha512",
+		key: None, salt: None, personal: None,
+		data: b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmn\
+			hijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu",
+		hash: b"\x8e\x95\x9b\x75\xda\xe3\x13\xda\x8c\xf4\xf7\x28\x14\xfc\x14\x3f\x8f\x77\x79\xc6\xeb\x9f\x7f\xa1\x72\x99\xae\xad\xb6\x88\x90\x18\x50\x1d\x28\x9e\x49\x00\xf7\xe4\x33\x1b\x99\xde\xc4\xb5\x43\x3a\xc7\xd3\x29\xee\xb6\xdd\x26\x54\x5e\x96\xe5\x5b\x87\x4b\xe9\x09"
+	}.test();
+}
+
+
+#[test]
+fn test_blake2b_selftest() {
+	// RFC 7693, Appendix E
+	const EXPECTED: &[u8] = b"\xc2\x3a\x78\x00\xd9\x81\x23\xbd\x10\xf5\x06\xc6\x1e\x29\xda\x56\x03\xd7\x63\xb8\xbb\xad\x2e\x73\x7f\x5e\x76\x5a\x7b\xcc\xd4\x75";
+	
+	let hash = Hashes::Blake2b.hash();
+	let mut grand = hash.state(32, &HashParams::default()).unwrap();
+	for out_len in [20, 32, 48, 64].iter().copied() {
+		for in_len in [0, 3, 128, 129, 255, 1024].iter().copied() {
+			// Unkeyed hash
+			let (data, mut buf) = (selftest_seq(in_len, in_len as u32), vec![0; out_len]);
+			hash.hash(&mut buf, &data, &HashParams::default()).unwrap();
+			grand.update(&buf);
+			
+			// Keyed hash
+			let key = selftest_seq(out_len, out_len as u32);
+			hash.hash(&mut buf, &data, &HashParams{ key: Some(&key), ..Default::default() }).unwrap();
+			grand.update(&buf);
+		}
+	}
+	
+	let mut buf = vec![0; 32];
+	grand.finish(&mut buf).unwrap();
+	assert_eq!(buf, EXPECTED);
+}
+
+
+#[test]
+fn test_blake2b_params() {
+	let hash = Hashes::Blake2b.hash();
+	let (mut plain, mut zero) = (vec![0; 64], vec![0; 64]);
+	
+	// An all-zero salt and personalization are equal to none
+	hash.hash(&mut plain, b"Data", &HashParams::default()).unwrap();
+	let params = HashParams{ key: None, salt: Some(&[0; 16]), personal: Some(&[0; 16]) };
+	hash.hash(&mut zero, b"Data", &params).unwrap();
+	assert_eq!(plain, zero);
+	
+	// Different parameters yield different hashes
+	let mut other = vec![0; 64];
+	for params in [
+		HashParams{ key: Some(&[0; 16]), ..Default::default() },
+		HashParams{ salt: Some(&[1; 16]), ..Default::default() },
+		HashParams{ personal: Some(&[1; 16]), ..Default::default() }
+	].iter() {
+		hash.hash(&mut other, b"Data", params).unwrap();
+		assert_ne!(plain, other);
+	}
+	
+	// Test a too short key
+	let params = HashParams{ key: Some(&[0; 15]), ..Default::default() };
+	let err = hash.hash(&mut other, b"Data", &params).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Invalid key length"));
+	
+	// Test unknown names
+	assert_eq!(Hashes::from_name("Blake2s").err(), Some(LibsodiumError::Unsupported));
+}
+
+
+#[test]
+fn test_sha2_copy() {
+	// NIST: one million repetitions of "a"
+	let vectors: [(Hashes, &[u8]); 2] = [
+		(Hashes::Sha256, b"\xcd\xc7\x6e\x5c\x99\x14\xfb\x92\x81\xa1\xc7\xe2\x84\xd7\x3e\x67\xf1\x80\x9a\x48\xa4\x97\x20\x0e\x04\x6d\x39\xcc\xc7\x11\x2c\xd0"),
+		(Hashes::Sha512, b"\xe7\x18\x48\x3d\x0c\xe7\x69\x64\x4e\x2e\x42\xc7\xbc\x15\xb4\x63\x8e\x1f\x98\xb1\x3b\x20\x44\x28\x56\x32\xa8\x03\xaf\xa9\x73\xeb\xde\x0f\xf2\x44\x87\x7e\xa6\x0a\x4c\xb0\x43\x2c\xe5\x77\xc3\x1b\xeb\x00\x9c\x5c\x2c\x49\xaa\x2e\x4e\xad\xb2\x17\xad\x8c\xc0\x9b")
+	];
+	for (hashes, expected) in vectors.iter().copied() {
+		let hash = hashes.hash();
+		let mut state = hash.state(hash.info().hash_len, &HashParams::default()).unwrap();
+		assert_eq!(io::copy(&mut io::repeat(b'a').take(1_000_000), &mut state).unwrap(), 1_000_000);
+		
+		let mut buf = vec![0; expected.len()];
+		assert_eq!(state.finish(&mut buf).unwrap(), expected.len());
+		assert_eq!(buf, expected);
+	}
+}
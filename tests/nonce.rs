@@ -0,0 +1,141 @@
+use crypto_api_libsodium::{
+	LibsodiumError, Ciphers,
+	nonce::{ self, NonceSequence, CounterNonce, RandomNonce }
+};
+
+
+/// A helper macro to compare a `Box<dyn Error + 'static>` to a `LibsodiumError`
+macro_rules! compare_err {
+	($err:expr, $expected:expr) => (
+		assert_eq!(*$err.downcast_ref::<LibsodiumError>().unwrap(), $expected)
+	);
+}
+
+
+/// The key used for all tests
+const KEY: &[u8] = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f\x10\x11\x12\x13\x14\x15\x16\x17\x18\x19\x1a\x1b\x1c\x1d\x1e\x1f";
+
+
+/// A 12 byte nonce sequence that is backed by a 1 byte counter so that it can be exhausted in tests
+struct ShortCounter(CounterNonce);
+impl NonceSequence for ShortCounter {
+	fn nonce_len(&self) -> usize {
+		12
+	}
+	fn next_nonce(&mut self, buf: &mut[u8]) -> Result<usize, LibsodiumError> {
+		self.0.next_nonce(&mut buf[..1])?;
+		buf[1..12].iter_mut().for_each(|b| *b = 0);
+		Ok(12)
+	}
+}
+
+
+#[test]
+fn test_counter() {
+	// Test the little-endian increment order
+	let mut counter = CounterNonce::with_start(b"\xfe\xff\x00");
+	let mut nonce = [0; 3];
+	for expected in [b"\xfe\xff\x00", b"\xff\xff\x00", b"\x00\x00\x01", b"\x01\x00\x01"].iter() {
+		assert_eq!(counter.next_nonce(&mut nonce).unwrap(), 3);
+		assert_eq!(&nonce, *expected);
+	}
+	
+	// Exhaust a 1-byte counter
+	let mut counter = CounterNonce::with_start(b"\x2a");
+	let mut nonce = [0; 1];
+	for i in 0 ..= 255u8 {
+		counter.next_nonce(&mut nonce).unwrap();
+		assert_eq!(nonce[0], i.wrapping_add(0x2a));
+	}
+	assert_eq!(counter.next_nonce(&mut nonce).unwrap_err(), LibsodiumError::ResourceError);
+	
+	// Test a too small buffer
+	let mut counter = CounterNonce::new(12);
+	assert_eq!(counter.nonce_len(), 12);
+	assert_eq!(
+		counter.next_nonce(&mut [0; 11]).unwrap_err(),
+		LibsodiumError::ApiMisuse("Buffer is too small")
+	);
+}
+
+
+#[test]
+fn test_random() {
+	let mut random = RandomNonce::new(24);
+	let (mut a, mut b) = ([0; 24], [0; 24]);
+	assert_eq!(random.next_nonce(&mut a).unwrap(), 24);
+	assert_eq!(random.next_nonce(&mut b).unwrap(), 24);
+	assert_ne!(a, b);
+	assert_eq!(
+		random.next_nonce(&mut [0; 23]).unwrap_err(),
+		LibsodiumError::ApiMisuse("Buffer is too small")
+	);
+}
+
+
+#[test]
+fn test_seal_open() {
+	let cipher = Ciphers::ChaCha20Poly1305Ietf.aead_cipher().unwrap();
+	let mut counter = CounterNonce::new(12);
+	
+	// Seal two messages and ensure they use different nonces
+	let mut buf_a = b"Testolope".to_vec();
+	buf_a.resize(12 + 9 + 16, 0);
+	let mut buf_b = buf_a.clone();
+	assert_eq!(nonce::seal(cipher.as_ref(), &mut counter, &mut buf_a, 9, b"AD", KEY).unwrap(), 37);
+	assert_eq!(nonce::seal(cipher.as_ref(), &mut counter, &mut buf_b, 9, b"AD", KEY).unwrap(), 37);
+	assert_eq!(&buf_a[..12], &[0; 12]);
+	assert_eq!(&buf_b[..12], b"\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00");
+	assert_ne!(&buf_a[12..], &buf_b[12..]);
+	
+	// Open the messages
+	for buf in [&mut buf_a, &mut buf_b].iter_mut() {
+		assert_eq!(nonce::open(cipher.as_ref(), buf, 37, b"AD", KEY).unwrap(), 9);
+		assert_eq!(&buf[..9], b"Testolope");
+	}
+	
+	// Test a random nonce with an extended nonce cipher
+	let cipher = Ciphers::XChaCha20Poly1305Ietf.aead_cipher().unwrap();
+	let mut buf = b"Testolope".to_vec();
+	buf.resize(24 + 9 + 16, 0);
+	nonce::seal(cipher.as_ref(), &mut RandomNonce::new(24), &mut buf, 9, b"", KEY).unwrap();
+	assert_eq!(nonce::open(cipher.as_ref(), &mut buf, 49, b"", KEY).unwrap(), 9);
+	assert_eq!(&buf[..9], b"Testolope");
+}
+
+
+#[test]
+fn test_errors() {
+	let cipher = Ciphers::ChaCha20Poly1305Ietf.aead_cipher().unwrap();
+	
+	// Invalid nonce length and buffer size
+	let mut buf = vec![0; 12 + 9 + 16];
+	let err = nonce::seal(cipher.as_ref(), &mut CounterNonce::new(24), &mut buf, 9, b"", KEY)
+		.unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Invalid nonce length"));
+	let err = nonce::seal(cipher.as_ref(), &mut CounterNonce::new(12), &mut buf[..36], 9, b"", KEY)
+		.unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Buffer is too small"));
+	
+	// Tampered and truncated data
+	nonce::seal(cipher.as_ref(), &mut CounterNonce::new(12), &mut buf, 9, b"", KEY).unwrap();
+	buf[0] ^= 0x01;
+	let err = nonce::open(cipher.as_ref(), &mut buf, 37, b"", KEY).unwrap_err();
+	compare_err!(err, LibsodiumError::InvalidData);
+	let err = nonce::open(cipher.as_ref(), &mut buf, 11, b"", KEY).unwrap_err();
+	compare_err!(err, LibsodiumError::InvalidData);
+	let err = nonce::open(cipher.as_ref(), &mut buf[..36], 37, b"", KEY).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Buffer is too small"));
+	
+	// An exhausted sequence must not touch the buffer
+	let mut counter = ShortCounter(CounterNonce::new(1));
+	let mut buf = b"Testolope".to_vec();
+	buf.resize(12 + 9 + 16, 0);
+	for _ in 0..256 {
+		nonce::seal(cipher.as_ref(), &mut counter, &mut buf.clone(), 9, b"", KEY).unwrap();
+	}
+	let err = nonce::seal(cipher.as_ref(), &mut counter, &mut buf, 9, b"", KEY).unwrap_err();
+	compare_err!(err, LibsodiumError::ResourceError);
+	assert_eq!(&buf[..9], b"Testolope");
+	assert!(buf[9..].iter().all(|b| *b == 0));
+}
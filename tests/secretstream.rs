@@ -0,0 +1,310 @@
+use std::io::{ self, Read, Write };
+use crypto_api_libsodium::{
+	LibsodiumError,
+	secretstream::{
+		PushStream, PullStream, Tag, EncryptingWriter, DecryptingReader,
+		KEY_LEN, HEADER_LEN, CHUNK_OVERHEAD, DEFAULT_CHUNK_LEN
+	}
+};
+
+
+/// A helper macro to compare a `Box<dyn Error + 'static>` to a `LibsodiumError`
+macro_rules! compare_err {
+	($err:expr, $expected:expr) => (
+		assert_eq!(*$err.downcast_ref::<LibsodiumError>().unwrap(), $expected)
+	);
+}
+
+
+/// The key used for all tests
+const KEY: &[u8] = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f\x10\x11\x12\x13\x14\x15\x16\x17\x18\x19\x1a\x1b\x1c\x1d\x1e\x1f";
+
+
+/// A chunk of a stream
+struct Chunk {
+	pub plaintext: &'static[u8],
+	pub ad: &'static[u8],
+	pub tag: Tag
+}
+
+
+/// Encrypts `chunks` into a header and a list of encrypted chunks
+fn push(chunks: &[Chunk]) -> (Vec<u8>, Vec<Vec<u8>>) {
+	let mut header = vec![0; HEADER_LEN];
+	let mut stream = PushStream::new(&mut header, KEY).unwrap();
+	
+	let encrypted = chunks.iter().map(|chunk| {
+		let mut buf = chunk.plaintext.to_vec();
+		buf.resize(chunk.plaintext.len() + CHUNK_OVERHEAD, 0);
+		let chunk_len = stream.push(&mut buf, chunk.plaintext.len(), chunk.ad, chunk.tag).unwrap();
+		assert_eq!(chunk_len, buf.len());
+		buf
+	}).collect();
+	(header, encrypted)
+}
+
+
+#[test]
+fn test_vector() {
+	// A stream that has been created with libsodium directly
+	let header = b"\x39\x0c\x95\x27\xc8\x98\x44\xe5\xef\xa1\x41\x54\x8b\x51\x8d\x9e\xb7\x0c\x35\x67\x25\xe5\xde\x2f";
+	let chunks = [
+		Chunk{ plaintext: b"Hello", ad: b"", tag: Tag::Message },
+		Chunk{ plaintext: b" World", ad: b"ad", tag: Tag::Push },
+		Chunk{ plaintext: b"!", ad: b"", tag: Tag::Final }
+	];
+	let encrypted: [&[u8]; 3] = [
+		b"\x9d\x2b\xf8\x28\x36\xa1\xf5\xb2\x1c\x28\xc5\xf9\x28\x06\xe2\xf2\x2a\x9e\x81\x1b\x03\xf4",
+		b"\xfa\xc8\x73\x7e\x06\x18\x6c\xf4\xe7\x2b\xdc\xb7\xad\xad\x48\x09\x25\x81\xee\xc9\x45\x83\xab",
+		b"\x04\x92\xd6\x9b\x10\x24\x09\x78\xab\x48\xff\x2e\x7b\xae\x31\x98\x92\xd1"
+	];
+	
+	let mut stream = PullStream::new(header, KEY).unwrap();
+	for (chunk, encrypted) in chunks.iter().zip(encrypted.iter()) {
+		let mut buf = encrypted.to_vec();
+		let (plaintext_len, tag) = stream.pull(&mut buf, encrypted.len(), chunk.ad).unwrap();
+		assert_eq!(&buf[..plaintext_len], chunk.plaintext);
+		assert_eq!(tag, chunk.tag);
+	}
+	stream.finish().unwrap();
+}
+
+
+#[test]
+fn test_roundtrip() {
+	let chunks = [
+		Chunk{ plaintext: b"", ad: b"", tag: Tag::Message },
+		Chunk{ plaintext: &[0x2a; 4096], ad: b"", tag: Tag::Message },
+		Chunk{ plaintext: b"End of first message", ad: b"Header 1", tag: Tag::Push },
+		Chunk{ plaintext: b"Chunk before rekey", ad: b"", tag: Tag::Rekey },
+		Chunk{ plaintext: b"Chunk after rekey", ad: b"Header 2", tag: Tag::Message },
+		Chunk{ plaintext: b"Last chunk", ad: b"", tag: Tag::Final }
+	];
+	let (header, encrypted) = push(&chunks);
+	
+	// Decrypt the stream
+	let mut stream = PullStream::new(&header, KEY).unwrap();
+	for (chunk, mut buf) in chunks.iter().zip(encrypted) {
+		assert!(!stream.is_finalized());
+		let chunk_len = chunk.plaintext.len() + CHUNK_OVERHEAD;
+		let (plaintext_len, tag) = stream.pull(&mut buf, chunk_len, chunk.ad).unwrap();
+		assert_eq!(&buf[..plaintext_len], chunk.plaintext);
+		assert_eq!(tag, chunk.tag);
+	}
+	assert!(stream.is_finalized());
+	
+	// Test that the stream cannot be used after the final chunk
+	let err = stream.pull(&mut [0; CHUNK_OVERHEAD], CHUNK_OVERHEAD, b"").unwrap_err();
+	compare_err!(err, LibsodiumError::InvalidState);
+	assert_eq!(stream.rekey().unwrap_err(), LibsodiumError::InvalidState);
+	stream.finish().unwrap();
+}
+
+
+#[test]
+fn test_explicit_rekey() {
+	// Encrypt two chunks with an explicit rekey in between
+	let mut header = vec![0; HEADER_LEN];
+	let mut push_stream = PushStream::new(&mut header, KEY).unwrap();
+	
+	let (mut chunk0, mut chunk1) = (b"Chunk 0".to_vec(), b"Chunk 1".to_vec());
+	chunk0.resize(7 + CHUNK_OVERHEAD, 0);
+	chunk1.resize(7 + CHUNK_OVERHEAD, 0);
+	push_stream.push(&mut chunk0, 7, b"", Tag::Message).unwrap();
+	push_stream.rekey().unwrap();
+	push_stream.push(&mut chunk1, 7, b"", Tag::Final).unwrap();
+	
+	// The receiver must not be able to decrypt the second chunk without rekeying
+	let mut pull_stream = PullStream::new(&header, KEY).unwrap();
+	pull_stream.pull(&mut chunk0.clone(), chunk0.len(), b"").unwrap();
+	let err = pull_stream.pull(&mut chunk1.clone(), chunk1.len(), b"").unwrap_err();
+	compare_err!(err, LibsodiumError::InvalidData);
+	
+	// Decrypt the second chunk after rekeying
+	pull_stream.rekey().unwrap();
+	let (plaintext_len, tag) = pull_stream.pull(&mut chunk1, 7 + CHUNK_OVERHEAD, b"").unwrap();
+	assert_eq!(&chunk1[..plaintext_len], b"Chunk 1");
+	assert_eq!(tag, Tag::Final);
+	pull_stream.finish().unwrap();
+}
+
+
+#[test]
+fn test_errors() {
+	let chunks = [
+		Chunk{ plaintext: b"Chunk 0", ad: b"AD 0", tag: Tag::Message },
+		Chunk{ plaintext: b"Chunk 1", ad: b"AD 1", tag: Tag::Message },
+		Chunk{ plaintext: b"Chunk 2", ad: b"AD 2", tag: Tag::Final }
+	];
+	let (header, encrypted) = push(&chunks);
+	
+	// Test a forged chunk
+	let mut stream = PullStream::new(&header, KEY).unwrap();
+	let mut forged = encrypted[0].clone();
+	forged[3] ^= 0x01;
+	let err = stream.pull(&mut forged, encrypted[0].len(), b"AD 0").unwrap_err();
+	compare_err!(err, LibsodiumError::InvalidData);
+	
+	// Test wrong associated data
+	let err = stream.pull(&mut encrypted[0].clone(), encrypted[0].len(), b"AD 1").unwrap_err();
+	compare_err!(err, LibsodiumError::InvalidData);
+	
+	// Test reordered chunks (the state is not modified by failed pulls)
+	let err = stream.pull(&mut encrypted[1].clone(), encrypted[1].len(), b"AD 1").unwrap_err();
+	compare_err!(err, LibsodiumError::InvalidData);
+	stream.pull(&mut encrypted[0].clone(), encrypted[0].len(), b"AD 0").unwrap();
+	
+	// Test a too short chunk
+	let err = stream.pull(&mut [0; CHUNK_OVERHEAD - 1], CHUNK_OVERHEAD - 1, b"").unwrap_err();
+	compare_err!(err, LibsodiumError::InvalidData);
+	
+	// Test a truncated stream
+	stream.pull(&mut encrypted[1].clone(), encrypted[1].len(), b"AD 1").unwrap();
+	assert_eq!(stream.finish().unwrap_err(), LibsodiumError::Truncated);
+	
+	// Test a wrong key
+	let mut stream = PullStream::new(&header, &[0; KEY_LEN]).unwrap();
+	let err = stream.pull(&mut encrypted[0].clone(), encrypted[0].len(), b"AD 0").unwrap_err();
+	compare_err!(err, LibsodiumError::InvalidData);
+	
+	// Test parameter checks
+	assert_eq!(
+		PushStream::new(&mut [0; HEADER_LEN - 1], KEY).err(),
+		Some(LibsodiumError::ApiMisuse("Buffer is too small"))
+	);
+	assert_eq!(
+		PushStream::new(&mut [0; HEADER_LEN], &[0; KEY_LEN - 1]).err(),
+		Some(LibsodiumError::ApiMisuse("Invalid key length"))
+	);
+	assert_eq!(
+		PullStream::new(&[0; HEADER_LEN + 1], KEY).err(),
+		Some(LibsodiumError::ApiMisuse("Invalid header length"))
+	);
+	
+	let mut stream = PushStream::new(&mut [0; HEADER_LEN], KEY).unwrap();
+	let err = stream.push(&mut [0; CHUNK_OVERHEAD + 3], 4, b"", Tag::Message).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Buffer is too small"));
+	
+	// Test that the stream cannot be used after the final chunk
+	stream.push(&mut [0; CHUNK_OVERHEAD], 0, b"", Tag::Final).unwrap();
+	let err = stream.push(&mut [0; CHUNK_OVERHEAD], 0, b"", Tag::Message).unwrap_err();
+	compare_err!(err, LibsodiumError::InvalidState);
+}
+
+
+/// Encrypts `data` with `EncryptingWriter` using a chunk length of `chunk_len`
+fn encrypt_io(data: &[u8], chunk_len: usize) -> Vec<u8> {
+	let mut writer = EncryptingWriter::with_chunk_len(Vec::new(), KEY, chunk_len).unwrap();
+	
+	// Write the data in odd pieces to test the buffering
+	for piece in data.chunks(7) {
+		writer.write_all(piece).unwrap();
+	}
+	writer.finish().unwrap()
+}
+/// Decrypts `data` with `DecryptingReader` using a chunk length of `chunk_len`
+fn decrypt_io(data: &[u8], chunk_len: usize) -> Result<Vec<u8>, io::Error> {
+	let mut reader = DecryptingReader::with_chunk_len(data, KEY, chunk_len)?;
+	let mut plaintext = Vec::new();
+	reader.read_to_end(&mut plaintext)?;
+	Ok(plaintext)
+}
+/// Asserts that `result` is an `io::Error` of `kind` that wraps `expected`
+fn assert_io_err<T: std::fmt::Debug>(result: Result<T, io::Error>, kind: io::ErrorKind,
+	expected: LibsodiumError)
+{
+	let err = result.unwrap_err();
+	assert_eq!(err.kind(), kind);
+	assert_eq!(*err.get_ref().unwrap().downcast_ref::<LibsodiumError>().unwrap(), expected);
+}
+
+
+#[test]
+fn test_io_roundtrip() {
+	const CHUNK_LEN: usize = 64;
+	for len in [0, 1, CHUNK_LEN - 1, CHUNK_LEN, CHUNK_LEN + 1, 3 * CHUNK_LEN, 1000].iter() {
+		let data: Vec<u8> = (0..*len).map(|i| i as u8).collect();
+		let encrypted = encrypt_io(&data, CHUNK_LEN);
+		
+		// Validate the layout (header + full chunks + final chunk which may be full or empty)
+		let chunks = std::cmp::max(len.div_ceil(CHUNK_LEN), 1);
+		assert_eq!(encrypted.len(), HEADER_LEN + len + chunks * CHUNK_OVERHEAD);
+		assert_eq!(decrypt_io(&encrypted, CHUNK_LEN).unwrap(), data);
+	}
+	
+	// Test the default chunk length with `io::copy`
+	let data = vec![0x2a; DEFAULT_CHUNK_LEN * 2 + 17];
+	let mut writer = EncryptingWriter::new(Vec::new(), KEY).unwrap();
+	io::copy(&mut data.as_slice(), &mut writer).unwrap();
+	let encrypted = writer.finish().unwrap();
+	
+	let mut reader = DecryptingReader::new(encrypted.as_slice(), KEY).unwrap();
+	let mut decrypted = Vec::new();
+	io::copy(&mut reader, &mut decrypted).unwrap();
+	assert_eq!(decrypted, data);
+}
+
+
+#[test]
+fn test_io_errors() {
+	const CHUNK_LEN: usize = 32;
+	let data = vec![0x2a; 3 * CHUNK_LEN + 5];
+	let encrypted = encrypt_io(&data, CHUNK_LEN);
+	let chunk_boundary = HEADER_LEN + 2 * (CHUNK_LEN + CHUNK_OVERHEAD);
+	
+	// Test a truncated header
+	assert_io_err(
+		decrypt_io(&encrypted[..HEADER_LEN - 1], CHUNK_LEN),
+		io::ErrorKind::UnexpectedEof, LibsodiumError::Truncated
+	);
+	
+	// Test a stream that is truncated at a chunk boundary
+	assert_io_err(
+		decrypt_io(&encrypted[..chunk_boundary], CHUNK_LEN),
+		io::ErrorKind::UnexpectedEof, LibsodiumError::Truncated
+	);
+	
+	// Test a stream that is truncated within a chunk
+	assert_io_err(
+		decrypt_io(&encrypted[..chunk_boundary + 7], CHUNK_LEN),
+		io::ErrorKind::InvalidData, LibsodiumError::InvalidData
+	);
+	
+	// Test a stream without final chunk
+	assert_io_err(
+		decrypt_io(&encrypted[..encrypted.len() - 5 - CHUNK_OVERHEAD], CHUNK_LEN),
+		io::ErrorKind::UnexpectedEof, LibsodiumError::Truncated
+	);
+	
+	// Test a forged chunk
+	let mut forged = encrypted.clone();
+	forged[chunk_boundary + 3] ^= 0x01;
+	assert_io_err(
+		decrypt_io(&forged, CHUNK_LEN),
+		io::ErrorKind::InvalidData, LibsodiumError::InvalidData
+	);
+	
+	// Test trailing data
+	let mut trailing = encrypted.clone();
+	trailing.push(0);
+	assert_io_err(
+		decrypt_io(&trailing, CHUNK_LEN),
+		io::ErrorKind::InvalidData, LibsodiumError::InvalidData
+	);
+	
+	// Test a wrong chunk length
+	assert_io_err(
+		decrypt_io(&encrypted, CHUNK_LEN + 1),
+		io::ErrorKind::InvalidData, LibsodiumError::InvalidData
+	);
+	
+	// Test invalid parameters
+	assert_io_err(
+		EncryptingWriter::with_chunk_len(Vec::new(), KEY, 0).map(|_| ()),
+		io::ErrorKind::InvalidInput, LibsodiumError::ApiMisuse("Invalid chunk length")
+	);
+	assert_io_err(
+		EncryptingWriter::new(Vec::new(), &KEY[1..]).map(|_| ()),
+		io::ErrorKind::InvalidInput, LibsodiumError::ApiMisuse("Invalid key length")
+	);
+}
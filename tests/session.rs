@@ -0,0 +1,160 @@
+use crypto_api_libsodium::{
+	LibsodiumError, Ciphers,
+	session::{ SealingSession, OpeningSession, RekeyPolicy, HEADER_LEN, REPLAY_WINDOW, MAX_EPOCH_SKIP }
+};
+
+
+/// A helper macro to compare a `Box<dyn Error + 'static>` to a `LibsodiumError`
+macro_rules! compare_err {
+	($err:expr, $expected:expr) => (
+		assert_eq!(*$err.downcast_ref::<LibsodiumError>().unwrap(), $expected)
+	);
+}
+
+
+/// The key used for all tests
+const KEY: &[u8] = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f\x10\x11\x12\x13\x14\x15\x16\x17\x18\x19\x1a\x1b\x1c\x1d\x1e\x1f";
+/// A message sealed in epoch 1 (i.e. with `KDF(KEY, id = 1, ctx = "AeadRkey")`) as sequence
+/// number 0 with `b"AD"` as associated data (generated with libsodium)
+const EPOCH_1_MESSAGE: &[u8] = b"\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x20\x12\x18\xd5\x65\xe5\x10\x88\x2b\x77\x28\xad\x46\x90\x0d\xe4\xc9\xbc\xe1\x1b\x2e\x7f\x06\x88\x2e";
+
+
+/// Seals `plaintext` and returns the message
+fn seal(session: &mut SealingSession, plaintext: &[u8]) -> Vec<u8> {
+	let mut buf = plaintext.to_vec();
+	buf.resize(HEADER_LEN + plaintext.len() + 16, 0);
+	let len = session.seal(&mut buf, plaintext.len(), b"AD").unwrap();
+	buf.truncate(len);
+	buf
+}
+/// Opens `message` and returns the plaintext
+fn open(session: &mut OpeningSession, message: &[u8]) -> Result<Vec<u8>, LibsodiumError> {
+	let mut buf = message.to_vec();
+	match session.open(&mut buf, message.len(), b"AD") {
+		Ok(len) => Ok(buf[..len].to_vec()),
+		Err(e) => Err(*e.downcast_ref::<LibsodiumError>().unwrap())
+	}
+}
+
+
+#[test]
+fn test_vector() {
+	let policy = RekeyPolicy{ max_messages: 1, max_bytes: u64::MAX };
+	let mut sealer = SealingSession::new(Ciphers::ChaCha20Poly1305Ietf, KEY, policy).unwrap();
+	seal(&mut sealer, b"Testolope");
+	assert_eq!(seal(&mut sealer, b"Testolope"), EPOCH_1_MESSAGE);
+	
+	let mut opener = OpeningSession::new(Ciphers::ChaCha20Poly1305Ietf, KEY).unwrap();
+	assert_eq!(open(&mut opener, EPOCH_1_MESSAGE).unwrap(), b"Testolope");
+	assert_eq!(opener.epoch(), 1);
+}
+
+
+#[test]
+fn test_epochs() {
+	// Rekey after three messages or 16 bytes
+	let policy = RekeyPolicy{ max_messages: 3, max_bytes: 16 };
+	for cipher in [Ciphers::ChaCha20Poly1305Ietf, Ciphers::XChaCha20Poly1305Ietf].iter().copied() {
+		let mut sealer = SealingSession::new(cipher, KEY, policy).unwrap();
+		let mut opener = OpeningSession::new(cipher, KEY).unwrap();
+		
+		let plaintexts: [&[u8]; 7] = [b"a", b"b", b"c", b"d", b"0123456789abcdef", b"e", b""];
+		let epochs = [0, 0, 0, 1, 2, 3, 3];
+		for (plaintext, epoch) in plaintexts.iter().zip(epochs.iter()) {
+			let message = seal(&mut sealer, plaintext);
+			assert_eq!(sealer.epoch(), *epoch);
+			assert_eq!(open(&mut opener, &message).unwrap(), *plaintext);
+			assert_eq!(opener.epoch(), *epoch);
+		}
+		
+		// Explicit rekey
+		sealer.rekey().unwrap();
+		let message = seal(&mut sealer, b"Testolope");
+		assert_eq!(open(&mut opener, &message).unwrap(), b"Testolope");
+		assert_eq!(opener.epoch(), 4);
+	}
+}
+
+
+#[test]
+fn test_out_of_order() {
+	let policy = RekeyPolicy{ max_messages: 100, max_bytes: u64::MAX };
+	let mut sealer = SealingSession::new(Ciphers::ChaCha20Poly1305Ietf, KEY, policy).unwrap();
+	let mut opener = OpeningSession::new(Ciphers::ChaCha20Poly1305Ietf, KEY).unwrap();
+	let messages: Vec<_> = (0 .. 110u8).map(|i| seal(&mut sealer, &[i])).collect();
+	
+	// Open the messages in reverse order within the window
+	for i in (0 .. REPLAY_WINDOW as usize).rev() {
+		assert_eq!(open(&mut opener, &messages[i]).unwrap(), [i as u8]);
+	}
+	
+	// Skip a message and ensure it can be received later within the window
+	assert_eq!(open(&mut opener, &messages[65]).unwrap(), [65]);
+	assert_eq!(open(&mut opener, &messages[64]).unwrap(), [64]);
+	
+	// Cross the epoch boundary and receive a late message from the previous epoch
+	assert_eq!(open(&mut opener, &messages[100]).unwrap(), [100]);
+	assert_eq!(opener.epoch(), 1);
+	assert_eq!(open(&mut opener, &messages[99]).unwrap(), [99]);
+	for (i, message) in messages.iter().enumerate().skip(101) {
+		assert_eq!(open(&mut opener, message).unwrap(), [i as u8]);
+	}
+	
+	// Messages that are too old
+	assert_eq!(open(&mut opener, &messages[0]).unwrap_err(), LibsodiumError::InvalidData);
+	assert_eq!(open(&mut opener, &messages[98]).unwrap(), [98]);
+	assert_eq!(open(&mut opener, &messages[98 - REPLAY_WINDOW as usize]).unwrap_err(), LibsodiumError::InvalidData);
+}
+
+
+#[test]
+fn test_errors() {
+	let policy = RekeyPolicy{ max_messages: 1, max_bytes: u64::MAX };
+	let mut sealer = SealingSession::new(Ciphers::ChaCha20Poly1305Ietf, KEY, policy).unwrap();
+	let mut opener = OpeningSession::new(Ciphers::ChaCha20Poly1305Ietf, KEY).unwrap();
+	
+	// Replays
+	let message = seal(&mut sealer, b"Testolope");
+	assert_eq!(open(&mut opener, &message).unwrap(), b"Testolope");
+	assert_eq!(open(&mut opener, &message).unwrap_err(), LibsodiumError::InvalidData);
+	
+	// A forged message from a future epoch must not advance the session
+	let mut forged = seal(&mut sealer, b"Testolope");
+	forged[HEADER_LEN] ^= 0x01;
+	assert_eq!(open(&mut opener, &forged).unwrap_err(), LibsodiumError::InvalidData);
+	assert_eq!(opener.epoch(), 0);
+	
+	// Too many skipped epochs and epochs older than the previous one
+	for _ in 0 .. MAX_EPOCH_SKIP {
+		seal(&mut sealer, b"");
+	}
+	let message = seal(&mut sealer, b"");
+	assert_eq!(open(&mut opener, &message).unwrap_err(), LibsodiumError::InvalidData);
+	
+	let mut sealer = SealingSession::new(Ciphers::ChaCha20Poly1305Ietf, KEY, policy).unwrap();
+	let mut opener = OpeningSession::new(Ciphers::ChaCha20Poly1305Ietf, KEY).unwrap();
+	let messages: Vec<_> = (0 .. 3).map(|_| seal(&mut sealer, b"")).collect();
+	open(&mut opener, &messages[1]).unwrap();
+	open(&mut opener, &messages[2]).unwrap();
+	assert_eq!(open(&mut opener, &messages[0]).unwrap_err(), LibsodiumError::InvalidData);
+	
+	
+	// Invalid parameters
+	assert_eq!(open(&mut opener, &[0; HEADER_LEN - 1]).unwrap_err(), LibsodiumError::InvalidData);
+	compare_err!(
+		opener.open(&mut [0; 16], 17, b"").unwrap_err(),
+		LibsodiumError::ApiMisuse("Buffer is too small")
+	);
+	compare_err!(
+		sealer.seal(&mut [0; HEADER_LEN + 16], 1, b"").unwrap_err(),
+		LibsodiumError::ApiMisuse("Buffer is too small")
+	);
+	assert_eq!(
+		SealingSession::new(Ciphers::ChaCha20Poly1305Ietf, &KEY[1..], policy).err(),
+		Some(LibsodiumError::ApiMisuse("Invalid key length"))
+	);
+	assert_eq!(
+		OpeningSession::new(Ciphers::ChaCha20Poly1305, KEY).err(),
+		Some(LibsodiumError::Unsupported)
+	);
+}
@@ -0,0 +1,126 @@
+use crypto_api_libsodium::{
+	LibsodiumError, SodiumSipHasher,
+	shorthash::{ self, KEY_LEN }
+};
+use std::{
+	collections::HashMap,
+	hash::{ Hasher, BuildHasher }
+};
+
+
+/// A helper macro to compare a `Box<dyn Error + 'static>` to a `LibsodiumError`
+macro_rules! compare_err {
+	($err:expr, $expected:expr) => (
+		assert_eq!(*$err.downcast_ref::<LibsodiumError>().unwrap(), $expected)
+	);
+}
+
+
+/// The key `00 01 02 ... 0f` used by the SipHash reference vectors
+const KEY: &[u8] = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f";
+
+
+struct ShortHashTestVector {
+	data_len: u8,
+	hash64: u64,
+	hash128: u128
+}
+impl ShortHashTestVector {
+	pub fn test(&self) {
+		// The reference vectors hash the message `00 01 02 ... (data_len - 1)`
+		let data: Vec<u8> = (0..self.data_len).collect();
+		assert_eq!(shorthash::siphash24(KEY, &data).unwrap(), self.hash64);
+		assert_eq!(shorthash::siphashx24(KEY, &data).unwrap(), self.hash128);
+		
+		// Test the `Hasher` with split writes
+		let mut hasher = SodiumSipHasher::with_key(KEY).unwrap().build_hasher();
+		data.chunks(4).for_each(|chunk| hasher.write(chunk));
+		assert_eq!(hasher.finish(), self.hash64);
+	}
+}
+
+
+#[test]
+fn test() {
+	// The reference vectors from https://github.com/veorq/SipHash
+	ShortHashTestVector {
+		data_len: 0,
+		hash64: 0x726fdb47dd0e0e31,
+		hash128: 0x930255c71472f66de6a825ba047f81a3
+	}.test();
+	
+	ShortHashTestVector {
+		data_len: 1,
+		hash64: 0x74f839c593dc67fd,
+		hash128: 0x45fc229b1159763444af996bd8c187da
+	}.test();
+	
+	ShortHashTestVector {
+		data_len: 15,
+		hash64: 0xa129ca6149be45e5,
+		hash128: 0xd9c3cf970fec087e11a8b03399e99354
+	}.test();
+	
+	ShortHashTestVector {
+		data_len: 63,
+		hash64: 0x958a324ceb064572,
+		hash128: 0x7cbd3f979a063e504a83502f77d15051
+	}.test();
+}
+
+
+#[test]
+fn test_long_messages() {
+	// Messages that exceed the inline buffer must be hashed like any other message
+	let data: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+	for len in [64, 65, 128, 129, 1000].iter().copied() {
+		for chunk_len in [1, 7, 64, 1000].iter().copied() {
+			let mut hasher = SodiumSipHasher::with_key(KEY).unwrap().build_hasher();
+			data[..len].chunks(chunk_len).for_each(|chunk| hasher.write(chunk));
+			assert_eq!(hasher.finish(), shorthash::siphash24(KEY, &data[..len]).unwrap());
+		}
+	}
+}
+
+
+#[test]
+fn test_errors() {
+	for key_len in [0, KEY_LEN - 1, KEY_LEN + 1].iter().copied() {
+		let key = vec![0; key_len];
+		let err = shorthash::siphash24(&key, b"Data").unwrap_err();
+		compare_err!(err, LibsodiumError::ApiMisuse("Invalid key length"));
+		let err = shorthash::siphashx24(&key, b"Data").unwrap_err();
+		compare_err!(err, LibsodiumError::ApiMisuse("Invalid key length"));
+		
+		let err = SodiumSipHasher::with_key(&key).err();
+		assert_eq!(err, Some(LibsodiumError::ApiMisuse("Invalid key length")));
+	}
+}
+
+
+#[test]
+fn test_build_hasher() {
+	// Explicit keys yield the same hashes across instances
+	let (a, b) = (SodiumSipHasher::with_key(KEY).unwrap(), SodiumSipHasher::with_key(KEY).unwrap());
+	assert_eq!(a.hash_one("Testolope"), b.hash_one("Testolope"));
+	assert_eq!(a.hash_one("Testolope"), a.clone().hash_one("Testolope"));
+	assert_ne!(a.hash_one("Testolope"), a.hash_one("Testolopf"));
+	
+	// Random keys yield different hashes
+	let (c, d) = (SodiumSipHasher::new().unwrap(), SodiumSipHasher::default());
+	assert_ne!(a.hash_one("Testolope"), c.hash_one("Testolope"));
+	assert_ne!(c.hash_one("Testolope"), d.hash_one("Testolope"));
+	assert_eq!(c.hash_one("Testolope"), c.hash_one("Testolope"));
+	
+	// Use the builder with a `HashMap`
+	let mut map: HashMap<String, usize, SodiumSipHasher> = HashMap::default();
+	for i in 0..1000 {
+		map.insert(format!("Key {}", i), i);
+	}
+	assert_eq!(map.len(), 1000);
+	assert!((0..1000).all(|i| map[&format!("Key {}", i)] == i));
+	
+	let mut map = HashMap::with_hasher(SodiumSipHasher::with_key(KEY).unwrap());
+	map.insert(7u64, "Seven");
+	assert_eq!(map.get(&7), Some(&"Seven"));
+}
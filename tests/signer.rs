@@ -1,5 +1,9 @@
+use std::io::{ self, Write };
 use crypto_api::{ rng::SecureRng, signer::SignerInfo };
-use crypto_api_libsodium::{ LibsodiumError, Signers, SystemRng };
+use crypto_api_libsodium::{
+	LibsodiumError, Signers, SystemRng,
+	signer::{ self, SEED_LEN, X25519_KEY_LEN, Ed25519phState, SigningContext }
+};
 
 
 /// A helper macro to compare a `Box<dyn Error + 'static>` to a `LibsodiumError`
@@ -133,7 +137,52 @@ impl SignerTestVector {
 		compare_err!(
 			signer.verify(self.message, &signature, self.pub_key).unwrap_err(),
 			LibsodiumError::InvalidData
-		)
+		);
+		
+		self.test_seed();
+		self.test_combined();
+	}
+	fn test_seed(&self) {
+		// Create signer and extract the seed
+		let signer = Signers::from_name(self.name).unwrap().seed_signer().unwrap();
+		let mut seed = vec![0; SEED_LEN];
+		assert_eq!(signer.sec_key_to_seed(&mut seed, self.sec_key).unwrap(), SEED_LEN);
+		
+		// Derive the secret key from the seed
+		let mut sec_key = vec![0; self.sec_key.len()];
+		assert_eq!(signer.sec_key_from_seed(&mut sec_key, &seed).unwrap(), self.sec_key.len());
+		assert_eq!(sec_key, self.sec_key);
+		
+		// Use the seed as secret key
+		let mut pub_key = vec![0; self.pub_key.len()];
+		signer.get_pub_key(&mut pub_key, &seed).unwrap();
+		assert_eq!(pub_key, self.pub_key);
+		
+		let mut signature = vec![0; self.signature.len()];
+		signer.sign(&mut signature, self.message, &seed).unwrap();
+		assert_eq!(signature, self.signature);
+	}
+	fn test_combined(&self) {
+		// Create signer (not all signers support combined signatures)
+		let signer = match Signers::from_name(self.name).unwrap().combined_signer() {
+			Ok(signer) => signer,
+			Err(_) => return
+		};
+		
+		// Sign the message
+		let mut signed = vec![0; self.signature.len() + self.message.len()];
+		assert_eq!(signer.sign_combined(&mut signed, self.message, self.sec_key).unwrap(), signed.len());
+		assert_eq!(&signed[..self.signature.len()], self.signature);
+		assert_eq!(&signed[self.signature.len()..], self.message);
+		
+		// Open the signed message
+		assert_eq!(signer.open(&signed, self.pub_key).unwrap(), self.message);
+		
+		// Damage the signature and truncate the blob
+		signed[0] ^= 0x01;
+		compare_err!(signer.open(&signed, self.pub_key).unwrap_err(), LibsodiumError::InvalidData);
+		let truncated = &signed[..self.signature.len() - 1];
+		compare_err!(signer.open(truncated, self.pub_key).unwrap_err(), LibsodiumError::Truncated);
 	}
 }
 
@@ -181,4 +230,451 @@ fn test() {
 		message: b"\x08\xb8\xb2\xb7\x33\x42\x42\x43\x76\x0f\xe4\x26\xa4\xb5\x49\x08\x63\x21\x10\xa6\x6c\x2f\x65\x91\xea\xbd\x33\x45\xe3\xe4\xeb\x98\xfa\x6e\x26\x4b\xf0\x9e\xfe\x12\xee\x50\xf8\xf5\x4e\x9f\x77\xb1\xe3\x55\xf6\xc5\x05\x44\xe2\x3f\xb1\x43\x3d\xdf\x73\xbe\x84\xd8\x79\xde\x7c\x00\x46\xdc\x49\x96\xd9\xe7\x73\xf4\xbc\x9e\xfe\x57\x38\x82\x9a\xdb\x26\xc8\x1b\x37\xc9\x3a\x1b\x27\x0b\x20\x32\x9d\x65\x86\x75\xfc\x6e\xa5\x34\xe0\x81\x0a\x44\x32\x82\x6b\xf5\x8c\x94\x1e\xfb\x65\xd5\x7a\x33\x8b\xbd\x2e\x26\x64\x0f\x89\xff\xbc\x1a\x85\x8e\xfc\xb8\x55\x0e\xe3\xa5\xe1\x99\x8b\xd1\x77\xe9\x3a\x73\x63\xc3\x44\xfe\x6b\x19\x9e\xe5\xd0\x2e\x82\xd5\x22\xc4\xfe\xba\x15\x45\x2f\x80\x28\x8a\x82\x1a\x57\x91\x16\xec\x6d\xad\x2b\x3b\x31\x0d\xa9\x03\x40\x1a\xa6\x21\x00\xab\x5d\x1a\x36\x55\x3e\x06\x20\x3b\x33\x89\x0c\xc9\xb8\x32\xf7\x9e\xf8\x05\x60\xcc\xb9\xa3\x9c\xe7\x67\x96\x7e\xd6\x28\xc6\xad\x57\x3c\xb1\x16\xdb\xef\xef\xd7\x54\x99\xda\x96\xbd\x68\xa8\xa9\x7b\x92\x8a\x8b\xbc\x10\x3b\x66\x21\xfc\xde\x2b\xec\xa1\x23\x1d\x20\x6b\xe6\xcd\x9e\xc7\xaf\xf6\xf6\xc9\x4f\xcd\x72\x04\xed\x34\x55\xc6\x8c\x83\xf4\xa4\x1d\xa4\xaf\x2b\x74\xef\x5c\x53\xf1\xd8\xac\x70\xbd\xcb\x7e\xd1\x85\xce\x81\xbd\x84\x35\x9d\x44\x25\x4d\x95\x62\x9e\x98\x55\xa9\x4a\x7c\x19\x58\xd1\xf8\xad\xa5\xd0\x53\x2e\xd8\xa5\xaa\x3f\xb2\xd1\x7b\xa7\x0e\xb6\x24\x8e\x59\x4e\x1a\x22\x97\xac\xbb\xb3\x9d\x50\x2f\x1a\x8c\x6e\xb6\xf1\xce\x22\xb3\xde\x1a\x1f\x40\xcc\x24\x55\x41\x19\xa8\x31\xa9\xaa\xd6\x07\x9c\xad\x88\x42\x5d\xe6\xbd\xe1\xa9\x18\x7e\xbb\x60\x92\xcf\x67\xbf\x2b\x13\xfd\x65\xf2\x70\x88\xd7\x8b\x7e\x88\x3c\x87\x59\xd2\xc4\xf5\xc6\x5a\xdb\x75\x53\x87\x8a\xd5\x75\xf9\xfa\xd8\x78\xe8\x0a\x0c\x9b\xa6\x3b\xcb\xcc\x27\x32\xe6\x94\x85\xbb\xc9\xc9\x0b\xfb\xd6\x24\x81\xd9\x08\x9b\xec\xcf\x80\xcf\xe2\xdf\x16\xa2\xcf\x65\xbd\x92\xdd\x59\x7b\x07\x07\xe0\x91\x7a\xf4\x8b\xbb\x75\xfe\xd4\x13\xd2\x38\xf5\x55\x5a\x7a\x56\x9d\x80\xc3\x41\x4a\x8d\x08\x59\xdc\x65\xa4\x61\x28\xba\xb2\x7a\xf8\x7a\x71\x31\x4f\x31\x8c\x78\x2b\x23\xeb\xfe\x80\x8b\x82\xb0\xce\x26\x40\x1d\x2e\x22\xf0\x4d\x83\xd1\x25\x5d\xc5\x1a\xdd\xd3\xb7\x5a\x2b\x1a\xe0\x78\x45\x04\xdf\x54\x3a\xf8\x96\x9b\xe3\xea\x70\x82\xff\x7f\xc9\x88\x8c\x14\x4d\xa2\xaf\x58\x42\x9e\xc9\x60\x31\xdb\xca\xd3\xda\xd9\xaf\x0d\xcb\xaa\xaf\x26\x8c\xb8\xfc\xff\xea\xd9\x4f\x3c\x7c\xa4\x95\xe0\x56\xa9\xb4\x7a\xcd\xb7\x51\xfb\x73\xe6\x66\xc6\xc6\x55\xad\xe8\x29\x72\x97\xd0\x7a\xd1\xba\x5e\x43\xf1\xbc\xa3\x23\x01\x65\x13\x39\xe2\x29\x04\xcc\x8c\x42\xf5\x8c\x30\xc0\x4a\xaf\xdb\x03\x8d\xda\x08\x47\xdd\x98\x8d\xcd\xa6\xf3\xbf\xd1\x5c\x4b\x4c\x45\x25\x00\x4a\xa0\x6e\xef\xf8\xca\x61\x78\x3a\xac\xec\x57\xfb\x3d\x1f\x92\xb0\xfe\x2f\xd1\xa8\x5f\x67\x24\x51\x7b\x65\xe6\x14\xad\x68\x08\xd6\xf6\xee\x34\xdf\xf7\x31\x0f\xdc\x82\xae\xbf\xd9\x04\xb0\x1e\x1d\xc5\x4b\x29\x27\x09\x4b\x2d\xb6\x8d\x6f\x90\x3b\x68\x40\x1a\xde\xbf\x5a\x7e\x08\xd7\x8f\xf4\xef\x5d\x63\x65\x3a\x65\x04\x0c\xf9\xbf\xd4\xac\xa7\x98\x4a\x74\xd3\x71\x45\x98\x67\x80\xfc\x0b\x16\xac\x45\x16\x49\xde\x61\x88\xa7\xdb\xdf\x19\x1f\x64\xb5\xfc\x5e\x2a\xb4\x7b\x57\xf7\xf7\x27\x6c\xd4\x19\xc1\x7a\x3c\xa8\xe1\xb9\x39\xae\x49\xe4\x88\xac\xba\x6b\x96\x56\x10\xb5\x48\x01\x09\xc8\xb1\x7b\x80\xe1\xb7\xb7\x50\xdf\xc7\x59\x8d\x5d\x50\x11\xfd\x2d\xcc\x56\x00\xa3\x2e\xf5\xb5\x2a\x1e\xcc\x82\x0e\x30\x8a\xa3\x42\x72\x1a\xac\x09\x43\xbf\x66\x86\xb6\x4b\x25\x79\x37\x65\x04\xcc\xc4\x93\xd9\x7e\x6a\xed\x3f\xb0\xf9\xcd\x71\xa4\x3d\xd4\x97\xf0\x1f\x17\xc0\xe2\xcb\x37\x97\xaa\x2a\x2f\x25\x66\x56\x16\x8e\x6c\x49\x6a\xfc\x5f\xb9\x32\x46\xf6\xb1\x11\x63\x98\xa3\x46\xf1\xa6\x41\xf3\xb0\x41\xe9\x89\xf7\x91\x4f\x90\xcc\x2c\x7f\xff\x35\x78\x76\xe5\x06\xb5\x0d\x33\x4b\xa7\x7c\x22\x5b\xc3\x07\xba\x53\x71\x52\xf3\xf1\x61\x0e\x4e\xaf\xe5\x95\xf6\xd9\xd9\x0d\x11\xfa\xa9\x33\xa1\x5e\xf1\x36\x95\x46\x86\x8a\x7f\x3a\x45\xa9\x67\x68\xd4\x0f\xd9\xd0\x34\x12\xc0\x91\xc6\x31\x5c\xf4\xfd\xe7\xcb\x68\x60\x69\x37\x38\x0d\xb2\xea\xaa\x70\x7b\x4c\x41\x85\xc3\x2e\xdd\xcd\xd3\x06\x70\x5e\x4d\xc1\xff\xc8\x72\xee\xee\x47\x5a\x64\xdf\xac\x86\xab\xa4\x1c\x06\x18\x98\x3f\x87\x41\xc5\xef\x68\xd3\xa1\x01\xe8\xa3\xb8\xca\xc6\x0c\x90\x5c\x15\xfc\x91\x08\x40\xb9\x4c\x00\xa0\xb9\xd0",
 		signature: b"\x0a\xab\x4c\x90\x05\x01\xb3\xe2\x4d\x7c\xdf\x46\x63\x32\x6a\x3a\x87\xdf\x5e\x48\x43\xb2\xcb\xdb\x67\xcb\xf6\xe4\x60\xfe\xc3\x50\xaa\x53\x71\xb1\x50\x8f\x9f\x45\x28\xec\xea\x23\xc4\x36\xd9\x4b\x5e\x8f\xcd\x4f\x68\x1e\x30\xa6\xac\x00\xa9\x70\x4a\x18\x8a\x03"
 	}.test();
-}
\ No newline at end of file
+}
+
+
+#[test]
+fn test_seed_errors() {
+	let signer = Signers::Ed25519.seed_signer().unwrap();
+	
+	// Test the seed and key lengths
+	let err = signer.sec_key_from_seed(&mut [0; 64], &[0; 31]).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Invalid seed length"));
+	let err = signer.sec_key_to_seed(&mut [0; 32], &[0; 32]).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Invalid key length"));
+	let err = signer.sign(&mut [0; 64], b"Data", &[0; 33]).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Invalid key length"));
+	
+	// Test the buffer lengths
+	let err = signer.sec_key_from_seed(&mut [0; 63], &[0; 32]).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Buffer is too small"));
+	let err = signer.sec_key_to_seed(&mut [0; 31], &[0; 64]).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Buffer is too small"));
+}
+
+
+#[test]
+fn test_ed25519ph() {
+	SignerInfo {
+		name: "Ed25519ph",
+		sig_len: 64, sec_key_len: 64, pub_key_len: 32
+	}.test();
+	
+	// RFC 8032, 7.3 (Test 1)
+	SignerTestVector {
+		name: "Ed25519ph",
+		
+		sec_key: b"\x83\x3f\xe6\x24\x09\x23\x7b\x9d\x62\xec\x77\x58\x75\x20\x91\x1e\x9a\x75\x9c\xec\x1d\x19\x75\x5b\x7d\xa9\x01\xb9\x6d\xca\x3d\x42\xec\x17\x2b\x93\xad\x5e\x56\x3b\xf4\x93\x2c\x70\xe1\x24\x50\x34\xc3\x54\x67\xef\x2e\xfd\x4d\x64\xeb\xf8\x19\x68\x34\x67\xe2\xbf",
+		pub_key: b"\xec\x17\x2b\x93\xad\x5e\x56\x3b\xf4\x93\x2c\x70\xe1\x24\x50\x34\xc3\x54\x67\xef\x2e\xfd\x4d\x64\xeb\xf8\x19\x68\x34\x67\xe2\xbf",
+		message: b"\x61\x62\x63",
+		signature: b"\x98\xa7\x02\x22\xf0\xb8\x12\x1a\xa9\xd3\x0f\x81\x3d\x68\x3f\x80\x9e\x46\x2b\x46\x9c\x7f\xf8\x76\x39\x49\x9b\xb9\x4e\x6d\xae\x41\x31\xf8\x50\x42\x46\x3c\x2a\x35\x5a\x20\x03\xd0\x62\xad\xf5\xaa\xa1\x0b\x8c\x61\xe6\x36\x06\x2a\xaa\xd1\x1c\x2a\x26\x08\x34\x06"
+	}.test();
+}
+
+
+#[test]
+fn test_ed25519ph_state() {
+	// RFC 8032, 7.3 (Test 1)
+	const SEED: &[u8] = b"\x83\x3f\xe6\x24\x09\x23\x7b\x9d\x62\xec\x77\x58\x75\x20\x91\x1e\x9a\x75\x9c\xec\x1d\x19\x75\x5b\x7d\xa9\x01\xb9\x6d\xca\x3d\x42";
+	const PUB_KEY: &[u8] = b"\xec\x17\x2b\x93\xad\x5e\x56\x3b\xf4\x93\x2c\x70\xe1\x24\x50\x34\xc3\x54\x67\xef\x2e\xfd\x4d\x64\xeb\xf8\x19\x68\x34\x67\xe2\xbf";
+	const SIGNATURE: &[u8] = b"\x98\xa7\x02\x22\xf0\xb8\x12\x1a\xa9\xd3\x0f\x81\x3d\x68\x3f\x80\x9e\x46\x2b\x46\x9c\x7f\xf8\x76\x39\x49\x9b\xb9\x4e\x6d\xae\x41\x31\xf8\x50\x42\x46\x3c\x2a\x35\x5a\x20\x03\xd0\x62\xad\xf5\xaa\xa1\x0b\x8c\x61\xe6\x36\x06\x2a\xaa\xd1\x1c\x2a\x26\x08\x34\x06";
+	
+	// Sign and verify the message in multiple parts
+	let mut state = Ed25519phState::new();
+	state.update(b"a");
+	state.update(b"");
+	state.update(b"bc");
+	let mut signature = [0; 64];
+	assert_eq!(state.sign(&mut signature, SEED).unwrap(), 64);
+	assert_eq!(signature.as_ref(), SIGNATURE);
+	
+	let mut state = Ed25519phState::new();
+	state.write_all(b"ab").unwrap();
+	state.write_all(b"c").unwrap();
+	state.verify(SIGNATURE, PUB_KEY).unwrap();
+	
+	// Sign a large message via `io::copy` and compare it with the one-shot API
+	let message: Vec<u8> = (0..1_000_000).map(|i| i as u8).collect();
+	let mut state = Ed25519phState::default();
+	io::copy(&mut message.as_slice(), &mut state).unwrap();
+	state.sign(&mut signature, SEED).unwrap();
+	Signers::Ed25519ph.signer().verify(&message, &signature, PUB_KEY).unwrap();
+	
+	let err = Signers::Ed25519.signer().verify(&message, &signature, PUB_KEY).unwrap_err();
+	compare_err!(err, LibsodiumError::InvalidData);
+	
+	// Test a modified message and invalid parameters
+	let mut state = Ed25519phState::new();
+	state.update(b"abd");
+	compare_err!(state.verify(SIGNATURE, PUB_KEY).unwrap_err(), LibsodiumError::InvalidData);
+	
+	let err = Ed25519phState::new().sign(&mut [0; 63], SEED).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Buffer is too small"));
+	let err = Ed25519phState::new().sign(&mut [0; 64], &SEED[1..]).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Invalid key length"));
+	let err = Ed25519phState::new().verify(&SIGNATURE[1..], PUB_KEY).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Invalid signature length"));
+	let err = Ed25519phState::new().verify(SIGNATURE, &PUB_KEY[1..]).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Invalid key length"));
+}
+
+
+#[test]
+fn test_combined_errors() {
+	let signer = Signers::Ed25519.combined_signer().unwrap();
+	
+	// Test the buffer and key lengths
+	let err = signer.sign_combined(&mut [0; 67], b"Data", &[0; 64]).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Buffer is too small"));
+	let err = signer.sign_combined(&mut [0; 68], b"Data", &[0; 63]).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Invalid key length"));
+	let err = signer.open(&[0; 68], &[0; 31]).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Invalid key length"));
+	
+	// Ed25519ph has no combined mode
+	assert_eq!(Signers::Ed25519ph.combined_signer().err(), Some(LibsodiumError::Unsupported));
+}
+
+
+#[test]
+fn test_x25519_conversion() {
+	// RFC 8032, 7.1 (Test 1 and 2); the X25519 keys and the shared secret were computed with libsodium
+	const SEED_A: &[u8] = b"\x9d\x61\xb1\x9d\xef\xfd\x5a\x60\xba\x84\x4a\xf4\x92\xec\x2c\xc4\x44\x49\xc5\x69\x7b\x32\x69\x19\x70\x3b\xac\x03\x1c\xae\x7f\x60";
+	const PUB_KEY_A: &[u8] = b"\xd7\x5a\x98\x01\x82\xb1\x0a\xb7\xd5\x4b\xfe\xd3\xc9\x64\x07\x3a\x0e\xe1\x72\xf3\xda\xa6\x23\x25\xaf\x02\x1a\x68\xf7\x07\x51\x1a";
+	const SEED_B: &[u8] = b"\x4c\xcd\x08\x9b\x28\xff\x96\xda\x9d\xb6\xc3\x46\xec\x11\x4e\x0f\x5b\x8a\x31\x9f\x35\xab\xa6\x24\xda\x8c\xf6\xed\x4f\xb8\xa6\xfb";
+	const PUB_KEY_B: &[u8] = b"\x3d\x40\x17\xc3\xe8\x43\x89\x5a\x92\xb7\x0a\xa7\x4d\x1b\x7e\xbc\x9c\x98\x2c\xcf\x2e\xc4\x96\x8c\xc0\xcd\x55\xf1\x2a\xf4\x66\x0c";
+	const X25519_SEC_KEY_A: &[u8] = b"\x30\x7c\x83\x86\x4f\x28\x33\xcb\x42\x7a\x2e\xf1\xc0\x0a\x01\x3c\xfd\xff\x27\x68\xd9\x80\xc0\xa3\xa5\x20\xf0\x06\x90\x4d\xe9\x4f";
+	const X25519_PUB_KEY_B: &[u8] = b"\x25\xc7\x04\xc5\x94\xb8\x8a\xfc\x00\xa7\x6b\x69\xd1\xed\x2b\x98\x4d\x7e\x22\x55\x0f\x3e\xd0\x80\x2d\x04\xfb\xcd\x07\xd3\x8d\x47";
+	const SHARED_SECRET: &[u8] = b"\x51\x66\xf2\x4a\x69\x18\x36\x8e\x2a\xf8\x31\xa4\xaf\xfa\xdd\x97\xaf\x0a\xc3\x26\xbd\xf1\x43\x59\x6c\x04\x59\x67\xcc\x00\x23\x0e";
+	
+	// Convert the keys (using a seed and a full secret key)
+	let convert = |seed: &[u8], pub_key: &[u8]| {
+		let mut full_sec_key = [0; 64];
+		Signers::Ed25519.seed_signer().unwrap().sec_key_from_seed(&mut full_sec_key, seed).unwrap();
+		
+		let (mut sec_key, mut sec_key_full) = ([0; X25519_KEY_LEN], [0; X25519_KEY_LEN]);
+		assert_eq!(signer::ed25519_sec_key_to_x25519(&mut sec_key, seed).unwrap(), X25519_KEY_LEN);
+		signer::ed25519_sec_key_to_x25519(&mut sec_key_full, &full_sec_key).unwrap();
+		assert_eq!(sec_key, sec_key_full);
+		
+		let mut x25519_pub_key = [0; X25519_KEY_LEN];
+		assert_eq!(signer::ed25519_pub_key_to_x25519(&mut x25519_pub_key, pub_key).unwrap(), X25519_KEY_LEN);
+		(sec_key, x25519_pub_key)
+	};
+	let (sec_key_a, pub_key_a) = convert(SEED_A, PUB_KEY_A);
+	let (sec_key_b, pub_key_b) = convert(SEED_B, PUB_KEY_B);
+	assert_eq!(sec_key_a.as_ref(), X25519_SEC_KEY_A);
+	assert_eq!(pub_key_b.as_ref(), X25519_PUB_KEY_B);
+	
+	// Both parties must compute the same shared secret
+	let (mut shared_a, mut shared_b) = ([0; X25519_KEY_LEN], [0; X25519_KEY_LEN]);
+	assert_eq!(signer::x25519(&mut shared_a, &sec_key_a, &pub_key_b).unwrap(), X25519_KEY_LEN);
+	assert_eq!(signer::x25519(&mut shared_b, &sec_key_b, &pub_key_a).unwrap(), X25519_KEY_LEN);
+	assert_eq!(shared_a, shared_b);
+	assert_eq!(shared_a.as_ref(), SHARED_SECRET);
+	
+	// Test invalid keys and parameters
+	let err = signer::ed25519_pub_key_to_x25519(&mut [0; 32], &[0xff; 32]).unwrap_err();
+	compare_err!(err, LibsodiumError::InvalidData);
+	let err = signer::ed25519_pub_key_to_x25519(&mut [0; 31], PUB_KEY_A).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Buffer is too small"));
+	let err = signer::ed25519_pub_key_to_x25519(&mut [0; 32], &PUB_KEY_A[1..]).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Invalid key length"));
+	let err = signer::ed25519_sec_key_to_x25519(&mut [0; 31], SEED_A).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Buffer is too small"));
+	let err = signer::ed25519_sec_key_to_x25519(&mut [0; 32], &SEED_A[1..]).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Invalid key length"));
+	let err = signer::x25519(&mut [0; 32], &sec_key_a, &[0; 32]).unwrap_err();
+	compare_err!(err, LibsodiumError::InvalidData);
+	let err = signer::x25519(&mut [0; 31], &sec_key_a, &pub_key_b).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Buffer is too small"));
+	let err = signer::x25519(&mut [0; 32], &sec_key_a[1..], &pub_key_b).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Invalid key length"));
+	let err = signer::x25519(&mut [0; 32], &sec_key_a, &pub_key_b[1..]).unwrap_err();
+	compare_err!(err, LibsodiumError::ApiMisuse("Invalid key length"));
+}
+
+
+#[test]
+fn test_batch() {
+	for signers in [Signers::Ed25519, Signers::Ed25519ph].iter().copied() {
+		let (signer, verifier) = (signers.signer(), signers.batch_verifier().unwrap());
+		
+		// Create 100 signed messages
+		let mut items = Vec::new();
+		for i in 0..100u8 {
+			let (mut sec_key, mut pub_key, mut sig) = (vec![0; 64], vec![0; 32], vec![0; 64]);
+			signer.new_sec_key(&mut sec_key).unwrap();
+			signer.get_pub_key(&mut pub_key, &sec_key).unwrap();
+			signer.sign(&mut sig, &[i; 7], &sec_key).unwrap();
+			items.push((vec![i; 7], sig, pub_key));
+		}
+		
+		// Damage some items
+		items[0].1[0] ^= 0x01;
+		items[42].0[3] ^= 0x01;
+		items[43].2 = items[44].2.clone();
+		items[99].1.pop();
+		
+		// Verify the batch with different thread counts
+		let batch: Vec<(&[u8], &[u8], &[u8])> = items.iter()
+			.map(|(data, sig, pub_key)| (data.as_slice(), sig.as_slice(), pub_key.as_slice()))
+			.collect();
+		let expected = vec![
+			(0, LibsodiumError::InvalidData), (42, LibsodiumError::InvalidData),
+			(43, LibsodiumError::InvalidData), (99, LibsodiumError::ApiMisuse("Invalid signature length"))
+		];
+		for threads in [0, 1, 3, 8, 200].iter().copied() {
+			assert_eq!(verifier.verify_batch(&batch, threads).unwrap_err(), expected);
+			assert_eq!(verifier.verify_batch(&batch[1..42], threads), Ok(()));
+		}
+		assert_eq!(verifier.verify_batch(&[], 4), Ok(()));
+		
+		// The results must match `Signer::verify`
+		for (i, (data, sig, pub_key)) in batch.iter().enumerate() {
+			let expected = expected.iter().find(|(j, _)| *j == i).map(|(_, e)| *e);
+			let err = signer.verify(data, sig, pub_key).err();
+			assert_eq!(err.map(|e| *e.downcast_ref::<LibsodiumError>().unwrap()), expected);
+		}
+	}
+}
+
+
+#[test]
+fn test_ed25519_strict() {
+	SignerInfo {
+		name: "Ed25519Strict",
+		sig_len: 64, sec_key_len: 64, pub_key_len: 32
+	}.test();
+	
+	// Regular signatures are compatible with `Ed25519`
+	SignerTestVector {
+		name: "Ed25519Strict",
+		
+		sec_key: b"\x9d\x61\xb1\x9d\xef\xfd\x5a\x60\xba\x84\x4a\xf4\x92\xec\x2c\xc4\x44\x49\xc5\x69\x7b\x32\x69\x19\x70\x3b\xac\x03\x1c\xae\x7f\x60\xd7\x5a\x98\x01\x82\xb1\x0a\xb7\xd5\x4b\xfe\xd3\xc9\x64\x07\x3a\x0e\xe1\x72\xf3\xda\xa6\x23\x25\xaf\x02\x1a\x68\xf7\x07\x51\x1a",
+		pub_key: b"\xd7\x5a\x98\x01\x82\xb1\x0a\xb7\xd5\x4b\xfe\xd3\xc9\x64\x07\x3a\x0e\xe1\x72\xf3\xda\xa6\x23\x25\xaf\x02\x1a\x68\xf7\x07\x51\x1a",
+		message: b"",
+		signature: b"\xe5\x56\x43\x00\xc3\x60\xac\x72\x90\x86\xe2\xcc\x80\x6e\x82\x8a\x84\x87\x7f\x1e\xb8\xe5\xd9\x74\xd8\x73\xe0\x65\x22\x49\x01\x55\x5f\xb8\x82\x15\x90\xa3\x3b\xac\xc6\x1e\x39\x70\x1c\xf9\xb4\x6b\xd2\x5b\xf5\xf0\x59\x5b\xbe\x24\x65\x51\x41\x43\x8e\x7a\x10\x0b"
+	}.test();
+	
+	// The test vectors 0 to 11 from "Taming the many EdDSAs" (https://eprint.iacr.org/2020/1244)
+	// followed by additional edge cases as `(message, pub_key, signature, accepted by Ed25519,
+	// accepted by Ed25519Strict)`
+	type Entry = (&'static[u8], &'static[u8], &'static[u8], bool, bool);
+	let corpus: &[Entry] = &[
+		// 0: small-order `A` and `R`, `S = 0`
+		(
+			b"\x8c\x93\x25\x5d\x71\xdc\xab\x10\xe8\xf3\x79\xc2\x62\x00\xf3\xc7\xbd\x5f\x09\xd9\xbc\x30\x68\xd3\xef\x4e\xde\xb4\x85\x30\x22\xb6",
+			b"\xc7\x17\x6a\x70\x3d\x4d\xd8\x4f\xba\x3c\x0b\x76\x0d\x10\x67\x0f\x2a\x20\x53\xfa\x2c\x39\xcc\xc6\x4e\xc7\xfd\x77\x92\xac\x03\xfa",
+			b"\xc7\x17\x6a\x70\x3d\x4d\xd8\x4f\xba\x3c\x0b\x76\x0d\x10\x67\x0f\x2a\x20\x53\xfa\x2c\x39\xcc\xc6\x4e\xc7\xfd\x77\x92\xac\x03\x7a\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00",
+			false, false
+		),
+		// 1: small-order `A`, mixed-order `R`
+		(
+			b"\x9b\xd9\xf4\x4f\x4d\xcc\x75\xbd\x53\x1b\x56\xb2\xcd\x28\x0b\x0b\xb3\x8f\xc1\xcd\x6d\x12\x30\xe1\x48\x61\xd8\x61\xde\x09\x2e\x79",
+			b"\xc7\x17\x6a\x70\x3d\x4d\xd8\x4f\xba\x3c\x0b\x76\x0d\x10\x67\x0f\x2a\x20\x53\xfa\x2c\x39\xcc\xc6\x4e\xc7\xfd\x77\x92\xac\x03\xfa",
+			b"\xf7\xba\xde\xc5\xb8\xab\xea\xf6\x99\x58\x39\x92\x21\x9b\x7b\x22\x3f\x1d\xf3\xfb\xbe\xa9\x19\x84\x4e\x3f\x7c\x55\x4a\x43\xdd\x43\xa5\xbb\x70\x47\x86\xbe\x79\xfc\x47\x6f\x91\xd3\xf3\xf8\x9b\x03\x98\x4d\x80\x68\xdc\xf1\xbb\x7d\xfc\x66\x37\xb4\x54\x50\xac\x04",
+			false, false
+		),
+		// 2: mixed-order `A`, small-order `R`
+		(
+			b"\xae\xbf\x3f\x26\x01\xa0\xc8\xc5\xd3\x9c\xc7\xd8\x91\x16\x42\xf7\x40\xb7\x81\x68\x21\x8d\xa8\x47\x17\x72\xb3\x5f\x9d\x35\xb9\xab",
+			b"\xf7\xba\xde\xc5\xb8\xab\xea\xf6\x99\x58\x39\x92\x21\x9b\x7b\x22\x3f\x1d\xf3\xfb\xbe\xa9\x19\x84\x4e\x3f\x7c\x55\x4a\x43\xdd\x43",
+			b"\xc7\x17\x6a\x70\x3d\x4d\xd8\x4f\xba\x3c\x0b\x76\x0d\x10\x67\x0f\x2a\x20\x53\xfa\x2c\x39\xcc\xc6\x4e\xc7\xfd\x77\x92\xac\x03\xfa\x8c\x4b\xd4\x5a\xec\xac\xa5\xb2\x4f\xb9\x7b\xc1\x0a\xc2\x7a\xc8\x75\x1a\x7d\xfe\x1b\xaf\xf8\xb9\x53\xec\x9f\x58\x33\xca\x26\x0e",
+			false, false
+		),
+		// 3: mixed-order `A` and `R` (passes the cofactorless equation)
+		(
+			b"\x9b\xd9\xf4\x4f\x4d\xcc\x75\xbd\x53\x1b\x56\xb2\xcd\x28\x0b\x0b\xb3\x8f\xc1\xcd\x6d\x12\x30\xe1\x48\x61\xd8\x61\xde\x09\x2e\x79",
+			b"\xcd\xb2\x67\xce\x40\xc5\xcd\x45\x30\x6f\xa5\xd2\xf2\x97\x31\x45\x93\x87\xdb\xf9\xeb\x93\x3b\x7b\xd5\xae\xd9\xa7\x65\xb8\x8d\x4d",
+			b"\x90\x46\xa6\x47\x50\x44\x49\x38\xde\x19\xf2\x27\xbb\x80\x48\x5e\x92\xb8\x3f\xdb\x4b\x65\x06\xc1\x60\x48\x4c\x01\x6c\xc1\x85\x2f\x87\x90\x9e\x14\x42\x8a\x7a\x1d\x62\xe9\xf2\x2f\x3d\x3a\xd7\x80\x2d\xb0\x2e\xb2\xe6\x88\xb6\xc5\x2f\xcd\x66\x48\xa9\x8b\xd0\x09",
+			true, false
+		),
+		// 4: mixed-order `A` and `R` (passes the cofactored equation only)
+		(
+			b"\xe4\x7d\x62\xc6\x3f\x83\x0d\xc7\xa6\x85\x1a\x0b\x1f\x33\xae\x4b\xb2\xf5\x07\xfb\x6c\xff\xec\x40\x11\xea\xcc\xd5\x5b\x53\xf5\x6c",
+			b"\xcd\xb2\x67\xce\x40\xc5\xcd\x45\x30\x6f\xa5\xd2\xf2\x97\x31\x45\x93\x87\xdb\xf9\xeb\x93\x3b\x7b\xd5\xae\xd9\xa7\x65\xb8\x8d\x4d",
+			b"\x16\x0a\x1c\xb0\xdc\x9c\x02\x58\xcd\x0a\x7d\x23\xe9\x4d\x8f\xa8\x78\xbc\xb1\x92\x5f\x2c\x64\x24\x6b\x2d\xee\x17\x96\xbe\xd5\x12\x5e\xc6\xbc\x98\x2a\x26\x9b\x72\x3e\x06\x68\xe5\x40\x91\x1a\x9a\x6a\x58\x92\x1d\x69\x25\xe4\x34\xab\x10\xaa\x79\x40\x55\x1a\x09",
+			false, false
+		),
+		// 5: mixed-order `A`, prime-order `R` (passes the cofactored equation only)
+		(
+			b"\xe4\x7d\x62\xc6\x3f\x83\x0d\xc7\xa6\x85\x1a\x0b\x1f\x33\xae\x4b\xb2\xf5\x07\xfb\x6c\xff\xec\x40\x11\xea\xcc\xd5\x5b\x53\xf5\x6c",
+			b"\xcd\xb2\x67\xce\x40\xc5\xcd\x45\x30\x6f\xa5\xd2\xf2\x97\x31\x45\x93\x87\xdb\xf9\xeb\x93\x3b\x7b\xd5\xae\xd9\xa7\x65\xb8\x8d\x4d",
+			b"\x21\x12\x2a\x84\xe0\xb5\xfc\xa4\x05\x2f\x5b\x12\x35\xc8\x0a\x53\x78\x78\xb3\x8f\x31\x42\x35\x6b\x2c\x23\x84\xeb\xad\x46\x68\xb7\xe4\x0b\xc8\x36\xda\xc0\xf7\x10\x76\xf9\xab\xe3\xa5\x3f\x9c\x03\xc1\xce\xee\xdd\xb6\x58\xd0\x03\x04\x94\xac\xe5\x86\x68\x74\x05",
+			false, false
+		),
+		// 6: `L <= S < 2^253`
+		(
+			b"\x85\xe2\x41\xa0\x7d\x14\x8b\x41\xe4\x7d\x62\xc6\x3f\x83\x0d\xc7\xa6\x85\x1a\x0b\x1f\x33\xae\x4b\xb2\xf5\x07\xfb\x6c\xff\xec\x40",
+			b"\x44\x2a\xad\x9f\x08\x9a\xd9\xe1\x46\x47\xb1\xef\x90\x99\xa1\xff\x47\x98\xd7\x85\x89\xe6\x6f\x28\xec\xa6\x9c\x11\xf5\x82\xa6\x23",
+			b"\xe9\x6f\x66\xbe\x97\x6d\x82\xe6\x01\x50\xba\xec\xff\x99\x06\x68\x4a\xeb\xb1\xef\x18\x1f\x67\xa7\x18\x9a\xc7\x8e\xa2\x3b\x6c\x0e\x54\x7f\x76\x90\xa0\xe2\xdd\xcd\x04\xd8\x7d\xbc\x34\x90\xdc\x19\xb3\xb3\x05\x2f\x7f\xf0\x53\x8c\xb6\x8a\xfb\x36\x9b\xa3\xa5\x14",
+			false, false
+		),
+		// 7: `S >= 2^253`
+		(
+			b"\x85\xe2\x41\xa0\x7d\x14\x8b\x41\xe4\x7d\x62\xc6\x3f\x83\x0d\xc7\xa6\x85\x1a\x0b\x1f\x33\xae\x4b\xb2\xf5\x07\xfb\x6c\xff\xec\x40",
+			b"\x44\x2a\xad\x9f\x08\x9a\xd9\xe1\x46\x47\xb1\xef\x90\x99\xa1\xff\x47\x98\xd7\x85\x89\xe6\x6f\x28\xec\xa6\x9c\x11\xf5\x82\xa6\x23",
+			b"\x8c\xe5\xb9\x6c\x8f\x26\xd0\xab\x6c\x47\x95\x8c\x9e\x68\xb9\x37\x10\x4c\xd3\x6e\x13\xc3\x35\x66\xac\xd2\xfe\x8d\x38\xaa\x19\x42\x7e\x71\xf9\x8a\x47\x34\x74\xf2\xf1\x3f\x06\xf9\x7c\x20\xd5\x8c\xc3\xf5\x4b\x8b\xd0\xd2\x72\xf4\x2b\x69\x5d\xd7\xe8\x9a\x8c\x22",
+			false, false
+		),
+		// 8: mixed-order `A`, small-order `R`
+		(
+			b"\x9b\xed\xc2\x67\x42\x37\x25\xd4\x73\x88\x86\x31\xeb\xf4\x59\x88\xba\xd3\xdb\x83\x85\x1e\xe8\x5c\x85\xe2\x41\xa0\x7d\x14\x8b\x41",
+			b"\xf7\xba\xde\xc5\xb8\xab\xea\xf6\x99\x58\x39\x92\x21\x9b\x7b\x22\x3f\x1d\xf3\xfb\xbe\xa9\x19\x84\x4e\x3f\x7c\x55\x4a\x43\xdd\x43",
+			b"\xec\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\x7f\x03\xbe\x96\x78\xac\x10\x2e\xdc\xd9\x2b\x02\x10\xbb\x34\xd7\x42\x8d\x12\xff\xc5\xdf\x5f\x37\xe3\x59\x94\x12\x66\xa4\xe3\x5f\x0f",
+			false, false
+		),
+		// 9: mixed-order `A`, non-canonical small-order `R`
+		(
+			b"\x9b\xed\xc2\x67\x42\x37\x25\xd4\x73\x88\x86\x31\xeb\xf4\x59\x88\xba\xd3\xdb\x83\x85\x1e\xe8\x5c\x85\xe2\x41\xa0\x7d\x14\x8b\x41",
+			b"\xf7\xba\xde\xc5\xb8\xab\xea\xf6\x99\x58\x39\x92\x21\x9b\x7b\x22\x3f\x1d\xf3\xfb\xbe\xa9\x19\x84\x4e\x3f\x7c\x55\x4a\x43\xdd\x43",
+			b"\xec\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xca\x8c\x5b\x64\xcd\x20\x89\x82\xaa\x38\xd4\x93\x66\x21\xa4\x77\x5a\xa2\x33\xaa\x05\x05\x71\x1d\x8f\xdc\xfd\xaa\x94\x3d\x49\x08",
+			false, false
+		),
+		// 10: non-canonical small-order `A` (passes if the reduced `A` is hashed)
+		(
+			b"\xe9\x6b\x70\x21\xeb\x39\xc1\xa1\x63\xb6\xda\x4e\x30\x93\xdc\xd3\xf2\x13\x87\xda\x4c\xc4\x57\x2b\xe5\x88\xfa\xfa\xe2\x3c\x15\x5b",
+			b"\xec\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff",
+			b"\xa9\xd5\x52\x60\xf7\x65\x26\x1e\xb9\xb8\x4e\x10\x6f\x66\x5e\x00\xb8\x67\x28\x7a\x76\x19\x90\xd7\x13\x59\x63\xee\x0a\x7d\x59\xdc\xa5\xbb\x70\x47\x86\xbe\x79\xfc\x47\x6f\x91\xd3\xf3\xf8\x9b\x03\x98\x4d\x80\x68\xdc\xf1\xbb\x7d\xfc\x66\x37\xb4\x54\x50\xac\x04",
+			false, false
+		),
+		// 11: non-canonical small-order `A` (passes if the encoded `A` is hashed)
+		(
+			b"\x39\xa5\x91\xf5\x32\x1b\xbe\x07\xfd\x5a\x23\xdc\x2f\x39\xd0\x25\xd7\x45\x26\x61\x57\x46\x72\x7c\xee\xfd\x6e\x82\xae\x65\xc0\x6f",
+			b"\xec\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff",
+			b"\xa9\xd5\x52\x60\xf7\x65\x26\x1e\xb9\xb8\x4e\x10\x6f\x66\x5e\x00\xb8\x67\x28\x7a\x76\x19\x90\xd7\x13\x59\x63\xee\x0a\x7d\x59\xdc\xa5\xbb\x70\x47\x86\xbe\x79\xfc\x47\x6f\x91\xd3\xf3\xf8\x9b\x03\x98\x4d\x80\x68\xdc\xf1\xbb\x7d\xfc\x66\x37\xb4\x54\x50\xac\x04",
+			false, false
+		),
+		
+		// Additional edge cases
+		// a regular signature
+		(
+			b"strict ed25519",
+			b"\x03\xa1\x07\xbf\xf3\xce\x10\xbe\x1d\x70\xdd\x18\xe7\x4b\xc0\x99\x67\xe4\xd6\x30\x9b\xa5\x0d\x5f\x1d\xdc\x86\x64\x12\x55\x31\xb8",
+			b"\x91\xaa\x38\x3d\x28\x2d\x52\x40\x5d\x32\x3d\x73\x97\x6f\xc6\x24\xdb\x19\x8a\xff\x7e\x37\x4a\xa5\xa1\xeb\xdb\xc5\xcc\x0f\x53\x48\x4d\x56\xb5\xda\xcf\x8b\x2e\xd7\x04\xde\xe2\xdc\xe4\x30\xca\x2d\x31\xdd\x88\xdd\x33\x32\x9b\x3f\x71\x86\x50\xc1\x8c\x10\xae\x0d",
+			true, true
+		),
+		// `S + L` instead of `S`
+		(
+			b"strict ed25519",
+			b"\x03\xa1\x07\xbf\xf3\xce\x10\xbe\x1d\x70\xdd\x18\xe7\x4b\xc0\x99\x67\xe4\xd6\x30\x9b\xa5\x0d\x5f\x1d\xdc\x86\x64\x12\x55\x31\xb8",
+			b"\x91\xaa\x38\x3d\x28\x2d\x52\x40\x5d\x32\x3d\x73\x97\x6f\xc6\x24\xdb\x19\x8a\xff\x7e\x37\x4a\xa5\xa1\xeb\xdb\xc5\xcc\x0f\x53\x48\x3a\x2a\xab\x37\xea\xee\x40\x2f\xdb\x7a\xda\x7f\xc3\x2a\xa9\x42\x31\xdd\x88\xdd\x33\x32\x9b\x3f\x71\x86\x50\xc1\x8c\x10\xae\x1d",
+			false, false
+		),
+		// `A` with a small-order component (passes the cofactorless equation)
+		(
+			b"mixed A 2",
+			b"\xb5\x02\xff\x3d\x92\xe3\x1d\x81\x90\xb4\xaa\x4e\xa0\x41\x40\x05\x16\x7f\xad\x08\x9c\x4d\xe9\xda\xc8\xa2\xfc\x85\x0f\xed\x4f\x58",
+			b"\x8b\xc1\x94\xc7\xa3\x1b\x70\x1e\x5d\xdb\x2e\xa7\x5c\xdc\x2e\x3b\xfb\xa1\xcc\xdb\x80\x34\x24\xbb\xd8\x8e\x8e\xe8\xcf\xae\x01\xce\xc1\x57\x35\x0b\x3b\x16\x42\x4f\xf6\xab\xb2\x0f\x31\x7c\x30\xf4\x02\x34\x36\xfc\x50\xc8\x50\xa5\xcf\x78\xf9\xfb\x7b\x59\x84\x06",
+			true, false
+		),
+		// `A` and `R` with small-order components (passes the cofactorless equation)
+		(
+			b"mixed R 0",
+			b"\xb5\x02\xff\x3d\x92\xe3\x1d\x81\x90\xb4\xaa\x4e\xa0\x41\x40\x05\x16\x7f\xad\x08\x9c\x4d\xe9\xda\xc8\xa2\xfc\x85\x0f\xed\x4f\x58",
+			b"\xdc\xb2\xcf\x1c\xa5\x66\xd9\x43\x4d\x9e\xcb\x93\x95\x91\x1b\x07\x42\x1e\x11\x06\x31\x44\x31\x6e\xfa\x25\xcc\x1e\xc8\xe1\xe0\xad\xbf\xff\xf9\xd4\x8f\xdd\x0e\x45\x97\xb9\x71\x0c\x3f\xf4\x56\x7f\x28\x29\x7c\xd5\x6a\x55\x25\xaf\x1f\xd0\x25\x47\x2d\x80\xd8\x03",
+			true, false
+		),
+		// non-canonical encoding of the neutral element as `A`
+		(
+			b"strict ed25519",
+			b"\xee\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\x7f",
+			b"\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00",
+			false, false
+		),
+		// non-canonical encoding of the neutral element as `R`
+		(
+			b"strict ed25519",
+			b"\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00",
+			b"\xee\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\x7f\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00",
+			false, false
+		)
+	];
+	for (message, pub_key, signature, ed25519, strict) in corpus.iter().copied() {
+		let signed = [signature, message].concat();
+		for (signers, valid) in [(Signers::Ed25519, ed25519), (Signers::Ed25519Strict, strict)].iter() {
+			let (signer, combined) = (signers.signer(), signers.combined_signer().unwrap());
+			match valid {
+				true => {
+					signer.verify(message, signature, pub_key).unwrap();
+					assert_eq!(combined.open(&signed, pub_key).unwrap(), message);
+				},
+				false => {
+					let err = signer.verify(message, signature, pub_key).unwrap_err();
+					compare_err!(err, LibsodiumError::InvalidData);
+					let err = combined.open(&signed, pub_key).unwrap_err();
+					compare_err!(err, LibsodiumError::InvalidData);
+				}
+			}
+		}
+	}
+	
+	// The batch verifier must reject the same entries
+	let batch: Vec<(&[u8], &[u8], &[u8])> = corpus.iter().map(|e| (e.0, e.2, e.1)).collect();
+	let failed: Vec<_> = corpus.iter().enumerate().filter(|(_, e)| !e.4)
+		.map(|(i, _)| (i, LibsodiumError::InvalidData)).collect();
+	let verifier = Signers::Ed25519Strict.batch_verifier().unwrap();
+	assert_eq!(verifier.verify_batch(&batch, 4).unwrap_err(), failed);
+}
+
+
+#[test]
+fn test_context() {
+	const SEED: &[u8] = b"\x9d\x61\xb1\x9d\xef\xfd\x5a\x60\xba\x84\x4a\xf4\x92\xec\x2c\xc4\x44\x49\xc5\x69\x7b\x32\x69\x19\x70\x3b\xac\x03\x1c\xae\x7f\x60";
+	const PUB_KEY: &[u8] = b"\xd7\x5a\x98\x01\x82\xb1\x0a\xb7\xd5\x4b\xfe\xd3\xc9\x64\x07\x3a\x0e\xe1\x72\xf3\xda\xa6\x23\x25\xaf\x02\x1a\x68\xf7\x07\x51\x1a";
+	const SIG_V2: &[u8] = b"\xd9\xa4\xda\x05\x0c\x23\x8d\x82\x6c\x96\xb6\xda\x76\x04\x94\xfb\x82\x68\xac\x2e\x18\xc9\xa2\x1e\xf9\xdf\x3d\xbf\xfa\xc3\x86\x3c\x95\xe9\x1c\x6e\xca\x37\x9a\xa5\xdc\x17\x35\x95\xdd\x12\x2a\x50\x80\x6c\x73\xf6\x8c\x1d\x3c\xe1\x16\xf4\x61\xd3\x98\x8c\x9d\x04";
+	const SIG_UNVERSIONED: &[u8] = b"\x51\x6b\x67\x70\x38\xb6\x7e\x66\x6d\x66\x07\xb2\x85\xd0\xa3\xf0\x18\x4b\xcc\x4a\xf5\xe7\x87\xec\xf6\x5b\xaf\x80\x9f\x45\xfd\x05\xe1\x18\xff\x09\x6b\xb4\xc7\x9c\x8c\xda\xe9\x43\xff\xd2\xf1\x71\xda\xac\xcf\x50\x59\x97\x42\x96\x0a\x72\xdf\xb3\x69\xf9\x82\x06";
+	
+	let v2 = SigningContext::with_version(b"invoice", 2);
+	let unversioned = SigningContext::new(b"invoice");
+	
+	// Test the encoding
+	assert_eq!(
+		v2.payload(b"Data").unwrap(),
+		b"SIGNCTX1\x07invoice\x01\x02\x00\x00\x00Data".to_vec()
+	);
+	assert_eq!(
+		unversioned.payload(b"Data").unwrap(),
+		b"SIGNCTX1\x07invoice\x00\x00\x00\x00\x00Data".to_vec()
+	);
+	
+	for signers in [Signers::Ed25519, Signers::Ed25519Strict].iter().copied() {
+		let signer = signers.context_signer().unwrap();
+		
+		// Test the known answers
+		for (context, expected) in [(v2, SIG_V2), (unversioned, SIG_UNVERSIONED)].iter() {
+			let mut sig = vec![0; 64];
+			assert_eq!(signer.sign_with_context(&mut sig, context, b"Data", SEED).unwrap(), 64);
+			assert_eq!(sig, *expected);
+			signer.verify_with_context(context, b"Data", &sig, PUB_KEY).unwrap();
+			
+			// The signature is a regular signature over the payload but not over the data
+			signer.verify(&context.payload(b"Data").unwrap(), &sig, PUB_KEY).unwrap();
+			let err = signer.verify(b"Data", &sig, PUB_KEY).unwrap_err();
+			compare_err!(err, LibsodiumError::InvalidData);
+		}
+		
+		// Signatures must not verify under a different context
+		let others = [
+			unversioned,
+			SigningContext::with_version(b"invoice", 0), SigningContext::with_version(b"invoice", 3),
+			SigningContext::with_version(b"invoicf", 2), SigningContext::with_version(b"invoic", 2)
+		];
+		for context in others.iter() {
+			let err = signer.verify_with_context(context, b"Data", SIG_V2, PUB_KEY).unwrap_err();
+			compare_err!(err, LibsodiumError::InvalidData);
+		}
+		
+		// Test invalid labels
+		for label in [&[][..], &[0x2a; 256][..]].iter().copied() {
+			let context = SigningContext::new(label);
+			let err = signer.sign_with_context(&mut [0; 64], &context, b"Data", SEED).unwrap_err();
+			compare_err!(err, LibsodiumError::ApiMisuse("Invalid context length"));
+			let err = signer.verify_with_context(&context, b"Data", SIG_V2, PUB_KEY).unwrap_err();
+			compare_err!(err, LibsodiumError::ApiMisuse("Invalid context length"));
+		}
+		let context = SigningContext::new(&[0x2a; 255]);
+		signer.sign_with_context(&mut [0; 64], &context, b"", SEED).unwrap();
+	}
+}
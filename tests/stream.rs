@@ -0,0 +1,159 @@
+use crypto_api_libsodium::{ LibsodiumError, Ciphers, stream::Stream };
+
+
+/// A helper macro to compare a `Box<dyn Error + 'static>` to a `LibsodiumError`
+macro_rules! compare_err {
+	($err:expr, $expected:expr) => (
+		assert_eq!(*$err.downcast_ref::<LibsodiumError>().unwrap(), $expected)
+	);
+}
+
+
+/// The key used for all tests
+const KEY: &[u8] = b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f\x10\x11\x12\x13\x14\x15\x16\x17\x18\x19\x1a\x1b\x1c\x1d\x1e\x1f";
+/// The chunk length used for all tests
+const CHUNK_LEN: usize = 64;
+
+
+/// Creates a test plaintext
+fn plaintext(len: usize) -> Vec<u8> {
+	(0..len).map(|i| i as u8).collect()
+}
+/// Seals `plaintext` and returns the blob
+fn seal(stream: &Stream, plaintext: &[u8], prefix: &[u8], threads: usize) -> Vec<u8> {
+	let mut buf = plaintext.to_vec();
+	buf.resize(stream.encrypted_len(plaintext.len()), 0);
+	assert_eq!(stream.seal(&mut buf, plaintext.len(), b"AD", KEY, prefix, threads).unwrap(), buf.len());
+	buf
+}
+
+
+#[test]
+fn test_roundtrip() {
+	for cipher in [Ciphers::ChaCha20Poly1305Ietf, Ciphers::XChaCha20Poly1305Ietf].iter().copied() {
+		let stream = Stream::new(cipher, CHUNK_LEN).unwrap();
+		let prefix = vec![0x2a; stream.prefix_len()];
+		
+		for len in [0, 1, CHUNK_LEN - 1, CHUNK_LEN, CHUNK_LEN + 1, 10 * CHUNK_LEN + 3].iter().copied() {
+			let plaintext = plaintext(len);
+			let blob = seal(&stream, &plaintext, &prefix, 1);
+			
+			for threads in [0, 1, 3, 8, 100].iter().copied() {
+				// Sealing must be deterministic regardless of the thread count...
+				assert_eq!(seal(&stream, &plaintext, &prefix, threads), blob);
+				
+				// ...and opening must restore the plaintext
+				let mut buf = blob.clone();
+				assert_eq!(stream.open(&mut buf, blob.len(), b"AD", KEY, &prefix, threads).unwrap(), len);
+				assert_eq!(&buf[..len], plaintext.as_slice());
+			}
+		}
+	}
+}
+
+
+#[test]
+fn test_nonces() {
+	// Every chunk must be a normal AEAD ciphertext with `prefix || counter (u32 BE) || last flag`
+	let stream = Stream::new(Ciphers::ChaCha20Poly1305Ietf, CHUNK_LEN).unwrap();
+	let aead = Ciphers::ChaCha20Poly1305Ietf.aead_cipher().unwrap();
+	let prefix = b"Prefix!";
+	let plaintext = plaintext(2 * CHUNK_LEN + 1);
+	let blob = seal(&stream, &plaintext, prefix, 2);
+	
+	let nonces: [&[u8]; 3] = [
+		b"Prefix!\x00\x00\x00\x00\x00", b"Prefix!\x00\x00\x00\x01\x00", b"Prefix!\x00\x00\x00\x02\x01"
+	];
+	for (i, (chunk, nonce)) in plaintext.chunks(CHUNK_LEN).zip(nonces.iter()).enumerate() {
+		let mut buf = chunk.to_vec();
+		buf.resize(chunk.len() + 16, 0);
+		aead.seal(&mut buf, chunk.len(), b"AD", KEY, nonce).unwrap();
+		assert_eq!(buf, &blob[stream.chunk_range(i, blob.len()).unwrap()]);
+	}
+}
+
+
+#[test]
+fn test_random_access() {
+	let stream = Stream::new(Ciphers::XChaCha20Poly1305Ietf, CHUNK_LEN).unwrap();
+	let prefix = vec![0; stream.prefix_len()];
+	
+	for len in [0, CHUNK_LEN, 5 * CHUNK_LEN + 7].iter().copied() {
+		let plaintext = plaintext(len);
+		let blob = seal(&stream, &plaintext, &prefix, 4);
+		
+		// Open the chunks in reverse order
+		let chunk_count = std::cmp::max(plaintext.chunks(CHUNK_LEN).count(), 1);
+		for i in (0..chunk_count).rev() {
+			let mut buf = blob[stream.chunk_range(i, blob.len()).unwrap()].to_vec();
+			let plaintext_len = stream.open_chunk(&mut buf, i, blob.len(), b"AD", KEY, &prefix).unwrap();
+			
+			let expected = plaintext.chunks(CHUNK_LEN).nth(i).unwrap_or(&[]);
+			assert_eq!(&buf[..plaintext_len], expected);
+		}
+		assert_eq!(
+			stream.chunk_range(chunk_count, blob.len()).unwrap_err(),
+			LibsodiumError::ApiMisuse("Invalid chunk index")
+		);
+	}
+}
+
+
+#[test]
+fn test_errors() {
+	let stream = Stream::new(Ciphers::ChaCha20Poly1305Ietf, CHUNK_LEN).unwrap();
+	let prefix = vec![0; stream.prefix_len()];
+	let blob = seal(&stream, &plaintext(3 * CHUNK_LEN), &prefix, 2);
+	let open = |blob: &[u8], prefix: &[u8]| {
+		stream.open(&mut blob.to_vec(), blob.len(), b"AD", KEY, prefix, 2).unwrap_err()
+	};
+	
+	// Truncated blobs (at a chunk boundary and within a chunk)
+	let truncated = &blob[..2 * stream.encrypted_chunk_len()];
+	compare_err!(open(truncated, &prefix), LibsodiumError::InvalidData);
+	compare_err!(open(&blob[..blob.len() - 1], &prefix), LibsodiumError::InvalidData);
+	compare_err!(open(&blob[..2 * stream.encrypted_chunk_len() + 15], &prefix), LibsodiumError::InvalidData);
+	
+	// Reordered chunks
+	let mut reordered = blob.clone();
+	let (first, second) = reordered.split_at_mut(stream.encrypted_chunk_len());
+	first.swap_with_slice(&mut second[..stream.encrypted_chunk_len()]);
+	compare_err!(open(&reordered, &prefix), LibsodiumError::InvalidData);
+	
+	// Tampered chunks and wrong prefix
+	let mut tampered = blob.clone();
+	tampered[CHUNK_LEN + 20] ^= 0x01;
+	compare_err!(open(&tampered, &prefix), LibsodiumError::InvalidData);
+	compare_err!(open(&blob, &[1; 7]), LibsodiumError::InvalidData);
+	
+	// Invalid parameters
+	compare_err!(open(&blob, &[0; 8]), LibsodiumError::ApiMisuse("Invalid nonce length"));
+	compare_err!(
+		stream.seal(&mut [0; 79], 64, b"", KEY, &prefix, 1).unwrap_err(),
+		LibsodiumError::ApiMisuse("Buffer is too small")
+	);
+	compare_err!(
+		stream.seal(&mut [0; 80], 64, b"", &KEY[1..], &prefix, 1).unwrap_err(),
+		LibsodiumError::ApiMisuse("Invalid key length")
+	);
+	assert_eq!(Stream::new(Ciphers::ChaCha20Poly1305Ietf, 0).err(), Some(LibsodiumError::ApiMisuse("Invalid chunk length")));
+	assert_eq!(Stream::new(Ciphers::XSalsa20, CHUNK_LEN).err(), Some(LibsodiumError::Unsupported));
+}
+
+
+#[test]
+fn test_wipe_on_error() {
+	let stream = Stream::new(Ciphers::ChaCha20Poly1305Ietf, CHUNK_LEN).unwrap();
+	let prefix = vec![0; stream.prefix_len()];
+	let mut blob = seal(&stream, &plaintext(3 * CHUNK_LEN + 7), &prefix, 1);
+	
+	// Forge the last chunk; the valid chunks must not be released
+	let last = blob.len() - 1;
+	blob[last] ^= 0x01;
+	for threads in [1, 2, 4].iter().copied() {
+		let mut buf = blob.clone();
+		let err = stream.open(&mut buf, blob.len(), b"AD", KEY, &prefix, threads).unwrap_err();
+		compare_err!(err, LibsodiumError::InvalidData);
+		assert!(buf.iter().all(|b| *b == 0));
+	}
+}